@@ -0,0 +1,115 @@
+use std::io;
+use std::sync::Arc;
+
+use super::game::{self, Game};
+use super::input::Console;
+use super::ruleset::Ruleset;
+use super::strategy::{self, Strategy};
+
+// For 95% confidence intervals
+const Z: f64 = 1.96;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    pub games: usize,
+    // Shared wins count as a part of a win
+    pub wins: f64,
+    pub points: i64,
+}
+
+impl Entry {
+    pub fn win_rate(&self) -> f64 {
+        self.wins / self.games as f64
+    }
+
+    // The Wilson score interval around the win rate
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let n = self.games as f64;
+        let p = self.win_rate();
+        let denominator = 1.0 + Z * Z / n;
+        let center = (p + Z * Z / (2.0 * n)) / denominator;
+        let margin = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+        ((center - margin).max(0.0), (center + margin).min(1.0))
+    }
+
+    pub fn mean_score(&self) -> f64 {
+        self.points as f64 / self.games as f64
+    }
+}
+
+// Plays the strategies against each other, without any output. Every game is
+// a duplicate game from seed + i, so they all play the same dice and only
+// their choices make the difference. Best win rate first.
+pub fn run(ruleset: Arc<dyn Ruleset>, forced: bool, strategies: &mut [Box<dyn Strategy>], games: usize, seed: u64) -> Result<Vec<Entry>, String> {
+    if strategies.len() < 2 || strategies.len() > 8 {
+        return Err("The arena needs between 2 and 8 strategies".to_string());
+    }
+    if games == 0 {
+        return Err("Play at least one game".to_string());
+    }
+
+    let mut names = Vec::<String>::new();
+    for strategy in strategies.iter() {
+        names.push(game::unique_name(&strategy.name(), &names));
+    }
+    let mut entries = names.iter()
+        .map(|name| Entry { name: name.clone(), games, wins: 0.0, points: 0 })
+        .collect::<Vec<_>>();
+
+    for i in 0..games {
+        let console = Console::new(io::empty(), io::sink());
+        let mut game = Game::with_console(ruleset.clone(), forced, names.clone(), console)
+            .with_seed(seed.wrapping_add(i as u64), true);
+        while !game.is_over() {
+            let current = game.current;
+            strategy::play_turn(&mut game, strategies[current].as_mut())?;
+        }
+
+        let totals = game.players.iter().map(|p| p.score_card.get_total()).collect::<Vec<_>>();
+        let best = *totals.iter().max().unwrap();
+        let winners = totals.iter().filter(|&&t| t == best).count();
+        for (entry, &total) in entries.iter_mut().zip(&totals) {
+            entry.points += total as i64;
+            if total == best {
+                entry.wins += 1.0 / winners as f64;
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| b.wins.total_cmp(&a.wins));
+    Ok(entries)
+}
+
+pub fn format_results(entries: &[Entry]) -> String {
+    let mut lines = vec!["Win rate\t95% interval\tMean\tStrategy".to_string()];
+    for entry in entries {
+        let (low, high) = entry.confidence_interval();
+        lines.push(format!("{:.1}%\t\t{:.1}-{:.1}%\t{:.1}p\t{}",
+            entry.win_rate() * 100.0, low * 100.0, high * 100.0, entry.mean_score(), entry.name));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{Bot, Difficulty};
+    use crate::ruleset::Scandinavian;
+
+    #[test]
+    fn test_arena() {
+        let ruleset: Arc<dyn Ruleset> = Arc::new(Scandinavian);
+        let mut strategies: Vec<Box<dyn Strategy>> = [Difficulty::Heuristic, Difficulty::Random, Difficulty::Random].iter()
+            .enumerate()
+            .map(|(i, &d)| Box::new(Bot::new(d, ruleset.clone(), false).unwrap().with_seed(i as u64)) as Box<dyn Strategy>)
+            .collect();
+        let entries = run(ruleset, false, &mut strategies, 50, 3).unwrap();
+
+        assert_eq!(entries[0].name, "Heuristic Bot");
+        assert!(entries.iter().any(|e| e.name == "Random Bot 2"));
+        assert!((entries.iter().map(|e| e.wins).sum::<f64>() - 50.0).abs() < 1e-9);
+        let (low, high) = entries[0].confidence_interval();
+        assert!(low < entries[0].win_rate() && entries[0].win_rate() <= high);
+    }
+}
@@ -0,0 +1,288 @@
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use super::dice::DiceRoll;
+use super::dice_result::ResultType;
+use super::game::Pick;
+use super::outcomes::Outcomes;
+use super::ruleset::Ruleset;
+use super::solver;
+use super::strategy::{State, Strategy};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Difficulty {
+    // Rerolls and picks at random
+    Random,
+    // Never rerolls and takes the highest score
+    Greedy,
+    // Goes for straights or the most common value, and keeps the upper bonus in mind
+    Heuristic,
+    // Plays the strategy with the highest expected score
+    Solver,
+}
+
+impl Difficulty {
+    pub fn parse(name: &str) -> Option<Difficulty> {
+        match name {
+            "random" => Some(Difficulty::Random),
+            "greedy" => Some(Difficulty::Greedy),
+            "heuristic" => Some(Difficulty::Heuristic),
+            "solver" => Some(Difficulty::Solver),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Difficulty::Random => "Random",
+            Difficulty::Greedy => "Greedy",
+            Difficulty::Heuristic => "Heuristic",
+            Difficulty::Solver => "Solver",
+        }
+    }
+}
+
+// The computer players that come with the game, one strategy per difficulty
+pub struct Bot {
+    pub difficulty: Difficulty,
+    rng: StdRng,
+    solver: Option<solver::Solver>,
+    // For weighing up which row to give up, made the first time one is
+    outcomes: Option<Outcomes>,
+}
+
+impl Bot {
+    // The solver bot works out its whole strategy up front, which is slow unless it's cached
+    pub fn new(difficulty: Difficulty, ruleset: Arc<dyn Ruleset>, forced: bool) -> Result<Bot, String> {
+        let solver = match difficulty {
+            Difficulty::Solver => {
+                let mut solver = solver::Solver::cached(ruleset, forced)?;
+                solver.solve();
+                // The cache only saves time, a failed write doesn't stop the bot
+                let _ = solver.save(&solver.cache_path());
+                Some(solver)
+            },
+            _ => None,
+        };
+        Ok(Bot { difficulty, rng: StdRng::from_entropy(), solver, outcomes: None })
+    }
+
+    // Makes the random choices repeatable
+    pub fn with_seed(mut self, seed: u64) -> Bot {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    // The open row a whole turn scores least in on average when going for it,
+    // the best one to give up. Rolls with more than 6 dice have too many
+    // outcomes to go through, so they're sampled instead.
+    fn least_valuable(&mut self, state: &State) -> ResultType {
+        let ruleset = state.ruleset;
+        let values = if ruleset.dice_count() <= 6 && ruleset.faces() <= 6 {
+            let outcomes = self.outcomes.get_or_insert_with(|| Outcomes::new(ruleset.dice_count(), ruleset.faces()));
+            turn_values(state, outcomes)
+        } else {
+            sampled_values(state)
+        };
+        *state.open.iter().zip(values).min_by(|a, b| a.1.total_cmp(&b.1)).unwrap().0
+    }
+}
+
+impl Strategy for Bot {
+    fn name(&self) -> String {
+        format!("{} Bot", self.difficulty.name())
+    }
+
+    fn choose_keep(&mut self, state: &State) -> Vec<usize> {
+        let all = (0..state.dice.dice.len()).collect::<Vec<_>>();
+        match self.difficulty {
+            Difficulty::Random if self.rng.gen_bool(0.5) => all,
+            Difficulty::Random => all.into_iter().filter(|_| self.rng.gen_bool(0.5)).collect(),
+            Difficulty::Greedy => all,
+            Difficulty::Heuristic => heuristic_hold(state).unwrap_or(all),
+            Difficulty::Solver => {
+                let solver = self.solver.as_mut().unwrap();
+                solver.best_keep(state.dice, state.rerolls, state.card).0
+            },
+        }
+    }
+
+    // Good scores go in the column that multiplies them most, zeros in the one that multiplies least
+    fn choose_category(&mut self, state: &State) -> (Pick, usize) {
+        let results = &state.results;
+        let open = &state.open;
+
+        let pick = match self.difficulty {
+            Difficulty::Random => match results.choose(&mut self.rng) {
+                Some(result) => Pick::Result(result.result_type),
+                None => Pick::Strike(*open.choose(&mut self.rng).unwrap()),
+            },
+            Difficulty::Greedy => match results.iter().max_by_key(|r| r.score) {
+                Some(result) => Pick::Result(result.result_type),
+                None => Pick::Strike(self.least_valuable(state)),
+            },
+            Difficulty::Heuristic => match results.iter().max_by_key(|r| heuristic_value(r.result_type, r.score)) {
+                Some(result) => Pick::Result(result.result_type),
+                None => Pick::Strike(self.least_valuable(state)),
+            },
+            Difficulty::Solver => {
+                let solver = self.solver.as_mut().unwrap();
+                match solver.best_category(state.dice, state.card) {
+                    Some((result_type, _)) if results.iter().any(|r| r.result_type == result_type) => Pick::Result(result_type),
+                    Some((result_type, _)) if open.contains(&result_type) => Pick::Zero(result_type),
+                    _ => Pick::Strike(self.least_valuable(state)),
+                }
+            },
+        };
+
+        let mut columns = state.card.get_open_columns(pick.result_type());
+        columns.sort_by_key(|&c| state.ruleset.column_multiplier(c));
+        let column = match (self.difficulty, pick) {
+            (Difficulty::Random, _) => *columns.choose(&mut self.rng).unwrap(),
+            (_, Pick::Result(_)) => *columns.last().unwrap(),
+            _ => columns[0],
+        };
+        (pick, column)
+    }
+}
+
+// Keeps a run of four or more when a straight is still open, otherwise the
+// most common value. None to pick now.
+fn heuristic_hold(state: &State) -> Option<Vec<usize>> {
+    let dice = state.dice;
+    let made = [ResultType::LargeStraight, ResultType::FullStraight, ResultType::Straight, ResultType::FullHouse, ResultType::Yatsy];
+    if state.results.iter().any(|r| made.contains(&r.result_type)) {
+        return None;
+    }
+
+    let straights = [ResultType::SmallStraight, ResultType::LargeStraight, ResultType::FullStraight, ResultType::Straight];
+    let length = dice.longest_straight();
+    if state.open.iter().any(|t| straights.contains(t)) && length >= 4 {
+        let top = (length..=dice.faces).find(|&v| (v - length + 1..=v).all(|d| dice.dice.contains(&d))).unwrap();
+        return Some((top - length + 1..=top).map(|v| dice.dice.iter().position(|&d| d == v).unwrap()).collect());
+    }
+
+    let most = (1..=dice.faces).max_by_key(|&v| dice.get_count_for_value(v)).unwrap();
+    if dice.get_count_for_value(most) == dice.dice.len() as i32 {
+        return None;
+    }
+    Some((0..dice.dice.len()).filter(|&i| dice.dice[i] == most).collect())
+}
+
+// Upper rows are measured against three of a kind, which is what the bonus
+// needs. Chance is kept for a bad roll, everything else is worth its score.
+fn heuristic_value(result_type: ResultType, score: i32) -> i32 {
+    if result_type == ResultType::Chance {
+        return score - 10;
+    }
+    match (1..=6).find(|&v| ResultType::for_value(v) == Some(result_type)) {
+        Some(face) if score >= face * 3 => score + 10,
+        Some(face) => score - face,
+        None => score,
+    }
+}
+
+// The expected score of a turn spent on each open row, holding the best dice
+// for it. Scored against the card, so rows like a second Generala are worth
+// what they would be in this game.
+fn turn_values(state: &State, outcomes: &Outcomes) -> Vec<f64> {
+    let rerolls = state.ruleset.rerolls_per_turn().max(0) as usize;
+    let fresh = outcomes.keep(&[]).unwrap();
+    state.open.iter().map(|&result_type| {
+        let points = outcomes.outcomes().iter().map(|outcome| {
+            let roll = DiceRoll { dice: outcome.clone(), faces: outcomes.faces() };
+            state.ruleset.score_on_card(result_type, &roll, 1, state.card) as f64
+        }).collect();
+        outcomes.expected_values(&outcomes.roll_values(points, rerolls))[fresh]
+    }).collect()
+}
+
+// The average score of each open row over the same 100 rolls every time, so
+// the same card gives up the same row
+fn sampled_values(state: &State) -> Vec<f64> {
+    let rolls = (0..100)
+        .map(|seed| DiceRoll::roll(state.ruleset.dice_count(), state.ruleset.faces(), &mut StdRng::seed_from_u64(seed)))
+        .collect::<Vec<_>>();
+    state.open.iter().map(|&result_type| {
+        rolls.iter().map(|roll| state.ruleset.score_on_card(result_type, roll, 1, state.card) as f64).sum::<f64>() / rolls.len() as f64
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice_result::DiceResult;
+    use crate::game::Game;
+    use crate::input::Console;
+    use crate::ruleset;
+    use std::io;
+
+    #[test]
+    fn test_bots_finish_game() {
+        let ruleset: Arc<dyn Ruleset> = Arc::new(ruleset::Scandinavian);
+        let console = Console::new(io::empty(), io::sink());
+        let mut game = Game::with_console(ruleset.clone(), false, vec!["Kim".to_string()], console);
+        game.players[0].bot = Some(Box::new(Bot::new(Difficulty::Heuristic, ruleset.clone(), false).unwrap()));
+        for difficulty in [Difficulty::Greedy, Difficulty::Greedy, Difficulty::Random] {
+            game.add_bot(Box::new(Bot::new(difficulty, ruleset.clone(), false).unwrap()));
+        }
+        game = game.with_seed(7, false);
+
+        let names = game.players.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Kim", "Greedy Bot", "Greedy Bot 2", "Random Bot"]);
+
+        game.start();
+        assert!(game.is_over());
+        assert!(game.players.iter().all(|p| p.score_card.get_total() > 0));
+    }
+
+    #[test]
+    fn test_strike_on_card() {
+        let ruleset: Arc<dyn Ruleset> = Arc::new(ruleset::Generala);
+        let console = Console::new(io::empty(), io::sink());
+        let mut game = Game::with_console(ruleset.clone(), false, vec!["Kim".to_string()], console);
+        let card = &mut game.players[0].score_card;
+        card.add_result(DiceResult { result_type: ResultType::Yatsy, score: 50 }, 0);
+        for result_type in ruleset.categories() {
+            if ![ResultType::Ones, ResultType::Yatsy, ResultType::DoubleYatsy].contains(&result_type) {
+                card.strike(result_type, 0);
+            }
+        }
+        game.dice = DiceRoll { dice: vec![2, 3, 4, 5, 6], faces: 6 };
+
+        // With a Generala scored the second one is worth 100, far more than the ones
+        let mut bot = Bot::new(Difficulty::Greedy, ruleset.clone(), false).unwrap();
+        assert_eq!(bot.choose_category(&State::new(&game)), (Pick::Strike(ResultType::Ones), 0));
+    }
+
+    #[test]
+    fn test_strike_many_dice() {
+        let rules = r#"
+            name = "Many dice"
+            dice = 10
+            faces = 20
+
+            [[categories]]
+            name = "Twenties"
+            pattern = "face"
+            value = 20
+
+            [[categories]]
+            name = "All of a kind"
+            pattern = "of_a_kind"
+            count = 10
+        "#;
+        let ruleset: Arc<dyn Ruleset> = Arc::new(ruleset::Custom::from_toml(rules).unwrap());
+        let console = Console::new(io::empty(), io::sink());
+        let mut game = Game::with_console(ruleset.clone(), false, vec!["Kim".to_string()], console);
+        game.dice = DiceRoll { dice: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10], faces: 20 };
+
+        // Too many dice to go through every roll, so the rows are weighed on samples
+        let mut bot = Bot::new(Difficulty::Greedy, ruleset.clone(), false).unwrap();
+        assert_eq!(bot.choose_category(&State::new(&game)), (Pick::Strike(ResultType::Custom(1)), 0));
+        assert!(bot.outcomes.is_none());
+    }
+}
@@ -1,348 +1,553 @@
-#[allow(dead_code)]
-
-use rand::Rng;
-use std::fmt::Display;
-use std::fmt::Formatter;
-use std::fmt;
-
-#[derive(Clone)]
-pub struct DiceRoll {
-    pub dice: [i32; 5],
-}
-
-impl Display for DiceRoll {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "[{}, {}, {}, {}, {}]", self.dice[0], self.dice[1], self.dice[2], self.dice[3], self.dice[4])
-    }
-}
-
-impl DiceRoll {
-    pub fn new() -> DiceRoll {
-        DiceRoll {
-            dice: [
-                roll_dice(),
-                roll_dice(),
-                roll_dice(),
-                roll_dice(),
-                roll_dice(),
-            ],
-        }
-    }
-
-    pub fn reroll(&mut self, indices: Vec::<usize>) -> DiceRoll {
-        let mut new_dice = DiceRoll { dice: self.dice };
-        for i in indices {
-            if (0..5).contains(&i) {
-                new_dice.dice[i] = roll_dice();
-            }
-        }
-        new_dice
-    }
-
-    pub fn ones(&self) -> i32 {
-        self.get_value_sum(1)
-    }
-
-    pub fn twos(&self) -> i32 {
-        self.get_value_sum(2)
-    }
-
-    pub fn threes(&self) -> i32 {
-        self.get_value_sum(3)
-    }
-
-    pub fn fours(&self) -> i32 {
-        self.get_value_sum(4)
-    }
-
-    pub fn fives(&self) -> i32 {
-        self.get_value_sum(5)
-    }
-
-    pub fn sixes(&self) -> i32 {
-        self.get_value_sum(6)
-    }
-
-    pub fn pair(&self) -> i32 {
-        for i in (1..=6).rev() {
-            if self.get_count_for_value(i) >= 2 {
-                return i * 2;
-            }
-        }
-        0
-    }
-
-    pub fn two_pairs(&self) -> i32 {
-        let mut pairs = vec![];
-        for i in 1..=6 {
-            if self.get_count_for_value(i) >= 2 {
-                pairs.push(i);
-            }
-        }
-
-        match pairs.len() {
-            2 => (pairs[0] + pairs[1]) * 2,
-            _ => 0
-        }
-    }
-
-    pub fn three_of_a_kind(&self) -> i32 {
-        self.get_value_for_count(3) * 3
-    }
-
-    pub fn four_of_a_kind(&self) -> i32 {
-        self.get_value_for_count(4) * 4
-    }
-
-    pub fn small_straight(&self) -> i32 {
-        let mut dice = self.dice;
-        dice.sort();
-        match dice {
-            [1, 2, 3, 4, 5] => 15,
-            _ => 0
-        }
-    }
-
-    pub fn large_straight(&self) -> i32 {
-        let mut dice = self.dice;
-        dice.sort();
-        match dice {
-            [2, 3, 4, 5, 6] => 20,
-            _ => 0
-        }
-    }
-
-    pub fn full_house(&self) -> i32 {
-        let mut triple = 0;
-        let mut double = 0;
-        for i in 1..7 {
-            if self.get_count_for_value(i) == 3 {
-                triple = i;
-            } else if self.get_count_for_value(i) == 2 {
-                double = i;
-            }
-        }
-        if triple != 0 && double != 0 {
-            return triple * 3 + double * 2;
-        }
-        0
-    }
-
-    pub fn chance(&self) -> i32 {
-        self.dice.iter().sum()
-    }
-
-    pub fn yatsy(&self) -> i32 {
-        if self.dice.iter().all(|&x| x == self.dice[0]) {
-            return 50;
-        }
-        0
-    }
-
-    fn get_value_sum(&self, value: i32) -> i32 {
-        self.dice.iter().filter(|&&x| x == value).sum()
-    }
-
-    fn get_count_for_value(&self, value: i32) -> i32 {
-        self.dice.iter().filter(|&&x| x == value).count() as i32
-    }
-
-    fn get_value_for_count(&self, count: i32) -> i32 {
-        for i in (1..7).rev() {
-            if self.get_count_for_value(i) >= count {
-                return i;
-            }
-        }
-        0
-    }
-}
-
-fn roll_dice() -> i32 {
-    rand::thread_rng().gen_range(1..7)
-}
-
-#[cfg(test)]
-mod tests {
-    // Note this useful idiom: importing names from outer (for mod tests) scope.
-    use super::*;
-
-    #[test]
-    fn test_ones() {
-        let roll = DiceRoll {
-            dice: [1, 1, 2, 3, 4],
-        };
-        assert_eq!(roll.ones(), 2);
-    }
-
-    #[test]
-    fn test_twos() {
-        let roll = DiceRoll {
-            dice: [2, 2, 3, 4, 5],
-        };
-        assert_eq!(roll.twos(), 4);
-    }
-
-    #[test]
-    fn test_threes() {
-        let roll = DiceRoll {
-            dice: [3, 3, 3, 4, 5],
-        };
-        assert_eq!(roll.threes(), 9);
-    }
-
-    #[test]
-    fn test_fours() {
-        let roll = DiceRoll {
-            dice: [4, 4, 4, 4, 5],
-        };
-        assert_eq!(roll.fours(), 16);
-    }
-
-    #[test]
-    fn test_fives() {
-        let roll = DiceRoll {
-            dice: [5, 5, 5, 5, 5],
-        };
-        assert_eq!(roll.fives(), 25);
-    }
-
-    #[test]
-    fn test_sixes() {
-        let roll = DiceRoll {
-            dice: [6, 6, 6, 6, 6],
-        };
-        assert_eq!(roll.sixes(), 30);
-    }
-
-    #[test]
-    fn test_pair() {
-        let roll = DiceRoll {
-            dice: [6, 6, 6, 6, 6],
-        };
-        assert_eq!(roll.pair(), 12);
-
-        let roll = DiceRoll {
-            dice: [1, 2, 3, 4, 5],
-        };
-        assert_eq!(roll.pair(), 0);
-
-        let roll = DiceRoll {
-            dice: [1, 2, 3, 4, 4],
-        };
-        assert_eq!(roll.pair(), 8);
-    }
-
-    #[test]
-    fn test_two_pairs() {
-        let roll = DiceRoll {
-            dice: [6, 6, 6, 6, 6],
-        };
-        assert_eq!(roll.two_pairs(), 0);
-
-        let roll = DiceRoll {
-            dice: [1, 3, 3, 4, 1],
-        };
-        assert_eq!(roll.two_pairs(), 8);
-
-        let roll = DiceRoll {
-            dice: [2, 2, 4, 4, 4],
-        };
-        assert_eq!(roll.two_pairs(), 12);
-    }
-
-    #[test]
-    fn test_three_of_a_kind() {
-        let roll = DiceRoll {
-            dice: [2, 2, 4, 2, 2],
-        };
-        assert_eq!(roll.three_of_a_kind(), 6);
-
-        let roll = DiceRoll {
-            dice: [1, 3, 3, 4, 1],
-        };
-        assert_eq!(roll.three_of_a_kind(), 0);
-
-        let roll = DiceRoll {
-            dice: [5, 2, 5, 5, 4],
-        };
-        assert_eq!(roll.three_of_a_kind(), 15);
-    }
-
-    #[test]
-    fn test_four_of_a_kind() {
-        let roll = DiceRoll {
-            dice: [2, 2, 4, 2, 2],
-        };
-        assert_eq!(roll.four_of_a_kind(), 8);
-
-        let roll = DiceRoll {
-            dice: [3, 3, 3, 4, 1],
-        };
-        assert_eq!(roll.four_of_a_kind(), 0);
-
-        let roll = DiceRoll {
-            dice: [5, 5, 5, 5, 5],
-        };
-        assert_eq!(roll.four_of_a_kind(), 20);
-    }
-
-    #[test]
-    fn test_small_straight() {
-        let roll = DiceRoll {
-            dice: [1, 4, 5, 2, 3],
-        };
-        assert_eq!(roll.small_straight(), 15);
-
-        let roll = DiceRoll {
-            dice: [1, 5, 5, 2, 3],
-        };
-        assert_eq!(roll.small_straight(), 0);
-    }
-
-    #[test]
-    fn test_large_straight() {
-        let roll = DiceRoll {
-            dice: [6, 4, 5, 3, 2],
-        };
-        assert_eq!(roll.large_straight(), 20);
-
-        let roll = DiceRoll {
-            dice: [1, 5, 5, 2, 3],
-        };
-        assert_eq!(roll.large_straight(), 0);
-    }
-
-    #[test]
-    fn test_full_house() {
-        let roll = DiceRoll {
-            dice: [6, 6, 3, 3, 3],
-        };
-        assert_eq!(roll.full_house(), 21);
-
-        let roll = DiceRoll {
-            dice: [1, 5, 5, 2, 3],
-        };
-        assert_eq!(roll.full_house(), 0);
-    }
-
-    #[test]
-    fn test_chance() {
-        let roll = DiceRoll {
-            dice: [1, 2, 3, 4, 5],
-        };
-        assert_eq!(roll.chance(), 15);
-    }
-
-    #[test]
-    fn test_yatsy() {
-        let roll = DiceRoll {
-            dice: [1, 1, 1, 1, 1],
-        };
-        assert_eq!(roll.yatsy(), 50);
-
-        let roll = DiceRoll {
-            dice: [6, 4, 5, 3, 2],
-        };
-        assert_eq!(roll.yatsy(), 0);
-    }
-}
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiceRoll {
+    pub dice: Vec<i32>,
+    pub faces: i32,
+}
+
+impl Display for DiceRoll {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "[{}]", self.dice.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", "))
+    }
+}
+
+impl DiceRoll {
+    pub fn new(count: usize, faces: i32) -> DiceRoll {
+        DiceRoll::roll(count, faces, &mut rand::thread_rng())
+    }
+
+    pub fn roll(count: usize, faces: i32, rng: &mut impl Rng) -> DiceRoll {
+        DiceRoll {
+            dice: (0..count).map(|_| roll_dice(faces, rng)).collect(),
+            faces,
+        }
+    }
+
+    pub fn reroll(&mut self, indices: Vec::<usize>) -> DiceRoll {
+        self.reroll_with(indices, &mut rand::thread_rng())
+    }
+
+    // Always draws a full row of dice and keeps the rerolled ones, so what a
+    // die shows only depends on the stream and its position, not on which
+    // dice were kept
+    pub fn reroll_with(&self, indices: Vec::<usize>, rng: &mut impl Rng) -> DiceRoll {
+        let row = DiceRoll::roll(self.dice.len(), self.faces, rng);
+        let mut new_dice = self.clone();
+        for i in indices {
+            if (0..self.dice.len()).contains(&i) {
+                new_dice.dice[i] = row.dice[i];
+            }
+        }
+        new_dice
+    }
+
+    // Describes holding the dice at the indices, like "Hold 4 4 6"
+    pub fn format_hold(&self, held: &[usize]) -> String {
+        match held.len() {
+            0 => "Reroll everything".to_string(),
+            n if n == self.dice.len() => "Hold everything".to_string(),
+            _ => format!("Hold {}", held.iter().map(|&i| self.dice[i].to_string()).collect::<Vec<_>>().join(" ")),
+        }
+    }
+
+    pub fn ones(&self) -> i32 {
+        self.get_value_sum(1)
+    }
+
+    pub fn twos(&self) -> i32 {
+        self.get_value_sum(2)
+    }
+
+    pub fn threes(&self) -> i32 {
+        self.get_value_sum(3)
+    }
+
+    pub fn fours(&self) -> i32 {
+        self.get_value_sum(4)
+    }
+
+    pub fn fives(&self) -> i32 {
+        self.get_value_sum(5)
+    }
+
+    pub fn sixes(&self) -> i32 {
+        self.get_value_sum(6)
+    }
+
+    pub fn pair(&self) -> i32 {
+        for i in (1..=self.faces).rev() {
+            if self.get_count_for_value(i) >= 2 {
+                return i * 2;
+            }
+        }
+        0
+    }
+
+    pub fn two_pairs(&self) -> i32 {
+        let pairs = self.get_values_for_count(2);
+        match pairs.len() {
+            0 | 1 => 0,
+            _ => (pairs[0] + pairs[1]) * 2
+        }
+    }
+
+    pub fn three_pairs(&self) -> i32 {
+        let pairs = self.get_values_for_count(2);
+        match pairs.len() {
+            0..=2 => 0,
+            _ => (pairs[0] + pairs[1] + pairs[2]) * 2
+        }
+    }
+
+    pub fn three_of_a_kind(&self) -> i32 {
+        self.get_value_for_count(3) * 3
+    }
+
+    pub fn four_of_a_kind(&self) -> i32 {
+        self.get_value_for_count(4) * 4
+    }
+
+    pub fn five_of_a_kind(&self) -> i32 {
+        self.get_value_for_count(5) * 5
+    }
+
+    pub fn small_straight(&self) -> i32 {
+        if self.contains_all(1..=5) {
+            return 15;
+        }
+        0
+    }
+
+    pub fn large_straight(&self) -> i32 {
+        if self.contains_all(2..=6) {
+            return 20;
+        }
+        0
+    }
+
+    pub fn full_straight(&self) -> i32 {
+        if self.contains_all(1..=6) {
+            return 21;
+        }
+        0
+    }
+
+    pub fn full_house(&self) -> i32 {
+        self.get_group_sum(3, 2)
+    }
+
+    pub fn castle(&self) -> i32 {
+        self.get_group_sum(3, 3)
+    }
+
+    pub fn tower(&self) -> i32 {
+        self.get_group_sum(4, 2)
+    }
+
+    pub fn chance(&self) -> i32 {
+        self.dice.iter().sum()
+    }
+
+    pub fn yatsy(&self) -> i32 {
+        if self.dice.iter().all(|&x| x == self.dice[0]) {
+            return 50;
+        }
+        0
+    }
+
+    // American style scoring, where the kinds score the sum of all dice
+    pub fn three_of_a_kind_total(&self) -> i32 {
+        if self.get_value_for_count(3) > 0 {
+            return self.chance();
+        }
+        0
+    }
+
+    pub fn four_of_a_kind_total(&self) -> i32 {
+        if self.get_value_for_count(4) > 0 {
+            return self.chance();
+        }
+        0
+    }
+
+    // Length of the longest run of consecutive values, regardless of order
+    pub fn longest_straight(&self) -> i32 {
+        let mut longest = 0;
+        let mut current = 0;
+        for i in 1..=self.faces {
+            if self.dice.contains(&i) {
+                current += 1;
+                longest = longest.max(current);
+            } else {
+                current = 0;
+            }
+        }
+        longest
+    }
+
+    fn get_value_sum(&self, value: i32) -> i32 {
+        self.dice.iter().filter(|&&x| x == value).sum()
+    }
+
+    pub fn get_count_for_value(&self, value: i32) -> i32 {
+        self.dice.iter().filter(|&&x| x == value).count() as i32
+    }
+
+    // The highest value that appears at least `count` times, 0 if there is none
+    pub fn get_value_for_count(&self, count: i32) -> i32 {
+        for i in (1..=self.faces).rev() {
+            if self.get_count_for_value(i) >= count {
+                return i;
+            }
+        }
+        0
+    }
+
+    // All values that appear at least `count` times, highest first
+    fn get_values_for_count(&self, count: i32) -> Vec<i32> {
+        (1..=self.faces).rev().filter(|&i| self.get_count_for_value(i) >= count).collect()
+    }
+
+    // Best score for a group of `first` equal dice together with a group of
+    // `second` equal dice of another value, e.g. 3 and 2 for a full house
+    fn get_group_sum(&self, first: i32, second: i32) -> i32 {
+        let mut best = 0;
+        for a in self.get_values_for_count(first) {
+            for b in self.get_values_for_count(second) {
+                if a != b {
+                    best = best.max(a * first + b * second);
+                }
+            }
+        }
+        best
+    }
+
+    fn contains_all(&self, mut values: impl Iterator<Item = i32>) -> bool {
+        values.all(|v| self.dice.contains(&v))
+    }
+}
+
+fn roll_dice(faces: i32, rng: &mut impl Rng) -> i32 {
+    rng.gen_range(1..=faces)
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_ones() {
+        let roll = DiceRoll {
+            dice: vec![1, 1, 2, 3, 4],
+            faces: 6,
+        };
+        assert_eq!(roll.ones(), 2);
+    }
+
+    #[test]
+    fn test_twos() {
+        let roll = DiceRoll {
+            dice: vec![2, 2, 3, 4, 5],
+            faces: 6,
+        };
+        assert_eq!(roll.twos(), 4);
+    }
+
+    #[test]
+    fn test_threes() {
+        let roll = DiceRoll {
+            dice: vec![3, 3, 3, 4, 5],
+            faces: 6,
+        };
+        assert_eq!(roll.threes(), 9);
+    }
+
+    #[test]
+    fn test_fours() {
+        let roll = DiceRoll {
+            dice: vec![4, 4, 4, 4, 5],
+            faces: 6,
+        };
+        assert_eq!(roll.fours(), 16);
+    }
+
+    #[test]
+    fn test_fives() {
+        let roll = DiceRoll {
+            dice: vec![5, 5, 5, 5, 5],
+            faces: 6,
+        };
+        assert_eq!(roll.fives(), 25);
+    }
+
+    #[test]
+    fn test_sixes() {
+        let roll = DiceRoll {
+            dice: vec![6, 6, 6, 6, 6],
+            faces: 6,
+        };
+        assert_eq!(roll.sixes(), 30);
+    }
+
+    #[test]
+    fn test_pair() {
+        let roll = DiceRoll {
+            dice: vec![6, 6, 6, 6, 6],
+            faces: 6,
+        };
+        assert_eq!(roll.pair(), 12);
+
+        let roll = DiceRoll {
+            dice: vec![1, 2, 3, 4, 5],
+            faces: 6,
+        };
+        assert_eq!(roll.pair(), 0);
+
+        let roll = DiceRoll {
+            dice: vec![1, 2, 3, 4, 4],
+            faces: 6,
+        };
+        assert_eq!(roll.pair(), 8);
+    }
+
+    #[test]
+    fn test_two_pairs() {
+        let roll = DiceRoll {
+            dice: vec![6, 6, 6, 6, 6],
+            faces: 6,
+        };
+        assert_eq!(roll.two_pairs(), 0);
+
+        let roll = DiceRoll {
+            dice: vec![1, 3, 3, 4, 1],
+            faces: 6,
+        };
+        assert_eq!(roll.two_pairs(), 8);
+
+        let roll = DiceRoll {
+            dice: vec![2, 2, 4, 4, 4],
+            faces: 6,
+        };
+        assert_eq!(roll.two_pairs(), 12);
+    }
+
+    #[test]
+    fn test_three_of_a_kind() {
+        let roll = DiceRoll {
+            dice: vec![2, 2, 4, 2, 2],
+            faces: 6,
+        };
+        assert_eq!(roll.three_of_a_kind(), 6);
+
+        let roll = DiceRoll {
+            dice: vec![1, 3, 3, 4, 1],
+            faces: 6,
+        };
+        assert_eq!(roll.three_of_a_kind(), 0);
+
+        let roll = DiceRoll {
+            dice: vec![5, 2, 5, 5, 4],
+            faces: 6,
+        };
+        assert_eq!(roll.three_of_a_kind(), 15);
+    }
+
+    #[test]
+    fn test_four_of_a_kind() {
+        let roll = DiceRoll {
+            dice: vec![2, 2, 4, 2, 2],
+            faces: 6,
+        };
+        assert_eq!(roll.four_of_a_kind(), 8);
+
+        let roll = DiceRoll {
+            dice: vec![3, 3, 3, 4, 1],
+            faces: 6,
+        };
+        assert_eq!(roll.four_of_a_kind(), 0);
+
+        let roll = DiceRoll {
+            dice: vec![5, 5, 5, 5, 5],
+            faces: 6,
+        };
+        assert_eq!(roll.four_of_a_kind(), 20);
+    }
+
+    #[test]
+    fn test_small_straight() {
+        let roll = DiceRoll {
+            dice: vec![1, 4, 5, 2, 3],
+            faces: 6,
+        };
+        assert_eq!(roll.small_straight(), 15);
+
+        let roll = DiceRoll {
+            dice: vec![1, 5, 5, 2, 3],
+            faces: 6,
+        };
+        assert_eq!(roll.small_straight(), 0);
+    }
+
+    #[test]
+    fn test_large_straight() {
+        let roll = DiceRoll {
+            dice: vec![6, 4, 5, 3, 2],
+            faces: 6,
+        };
+        assert_eq!(roll.large_straight(), 20);
+
+        let roll = DiceRoll {
+            dice: vec![1, 5, 5, 2, 3],
+            faces: 6,
+        };
+        assert_eq!(roll.large_straight(), 0);
+    }
+
+    #[test]
+    fn test_full_house() {
+        let roll = DiceRoll {
+            dice: vec![6, 6, 3, 3, 3],
+            faces: 6,
+        };
+        assert_eq!(roll.full_house(), 21);
+
+        let roll = DiceRoll {
+            dice: vec![1, 5, 5, 2, 3],
+            faces: 6,
+        };
+        assert_eq!(roll.full_house(), 0);
+    }
+
+    #[test]
+    fn test_chance() {
+        let roll = DiceRoll {
+            dice: vec![1, 2, 3, 4, 5],
+            faces: 6,
+        };
+        assert_eq!(roll.chance(), 15);
+    }
+
+    #[test]
+    fn test_yatsy() {
+        let roll = DiceRoll {
+            dice: vec![1, 1, 1, 1, 1],
+            faces: 6,
+        };
+        assert_eq!(roll.yatsy(), 50);
+
+        let roll = DiceRoll {
+            dice: vec![6, 4, 5, 3, 2],
+            faces: 6,
+        };
+        assert_eq!(roll.yatsy(), 0);
+    }
+
+    #[test]
+    fn test_maxi_combinations() {
+        let roll = DiceRoll {
+            dice: vec![2, 2, 4, 4, 6, 6],
+            faces: 6,
+        };
+        assert_eq!(roll.two_pairs(), 20);
+        assert_eq!(roll.three_pairs(), 24);
+        assert_eq!(roll.castle(), 0);
+
+        let roll = DiceRoll {
+            dice: vec![3, 3, 3, 5, 5, 5],
+            faces: 6,
+        };
+        assert_eq!(roll.castle(), 24);
+        assert_eq!(roll.full_house(), 21);
+        assert_eq!(roll.tower(), 0);
+
+        let roll = DiceRoll {
+            dice: vec![1, 1, 6, 6, 6, 6],
+            faces: 6,
+        };
+        assert_eq!(roll.tower(), 26);
+        assert_eq!(roll.five_of_a_kind(), 0);
+
+        let roll = DiceRoll {
+            dice: vec![4, 1, 6, 3, 5, 2],
+            faces: 6,
+        };
+        assert_eq!(roll.full_straight(), 21);
+        assert_eq!(roll.small_straight(), 15);
+        assert_eq!(roll.large_straight(), 20);
+    }
+
+    #[test]
+    fn test_of_a_kind_total() {
+        let roll = DiceRoll {
+            dice: vec![2, 2, 4, 2, 6],
+            faces: 6,
+        };
+        assert_eq!(roll.three_of_a_kind_total(), 16);
+        assert_eq!(roll.four_of_a_kind_total(), 0);
+
+        let roll = DiceRoll {
+            dice: vec![5, 5, 5, 5, 1],
+            faces: 6,
+        };
+        assert_eq!(roll.four_of_a_kind_total(), 21);
+    }
+
+    #[test]
+    fn test_longest_straight() {
+        let roll = DiceRoll {
+            dice: vec![3, 1, 4, 2, 6],
+            faces: 6,
+        };
+        assert_eq!(roll.longest_straight(), 4);
+
+        let roll = DiceRoll {
+            dice: vec![6, 4, 5, 3, 2],
+            faces: 6,
+        };
+        assert_eq!(roll.longest_straight(), 5);
+
+        let roll = DiceRoll {
+            dice: vec![1, 1, 3, 5, 5],
+            faces: 6,
+        };
+        assert_eq!(roll.longest_straight(), 1);
+    }
+
+    #[test]
+    fn test_more_faces() {
+        let roll = DiceRoll {
+            dice: vec![8, 8, 7, 7, 7],
+            faces: 8,
+        };
+        assert_eq!(roll.pair(), 16);
+        assert_eq!(roll.two_pairs(), 30);
+        assert_eq!(roll.three_of_a_kind(), 21);
+        assert_eq!(roll.full_house(), 37);
+
+        let roll = DiceRoll {
+            dice: vec![4, 5, 6, 7, 8],
+            faces: 8,
+        };
+        assert_eq!(roll.longest_straight(), 5);
+        assert_eq!(roll.large_straight(), 0);
+    }
+
+    #[test]
+    fn test_reroll() {
+        let mut roll = DiceRoll::new(6, 8);
+        assert_eq!(roll.dice.len(), 6);
+        assert!(roll.dice.iter().all(|d| (1..=8).contains(d)));
+
+        let kept = roll.dice[1];
+        let roll = roll.reroll(vec![0, 2, 3, 4, 5, 9]);
+        assert_eq!(roll.dice.len(), 6);
+        assert_eq!(roll.dice[1], kept);
+        assert!(roll.dice.iter().all(|d| (1..=8).contains(d)));
+    }
+}
@@ -1,179 +1,210 @@
-use std::fmt::Display;
-use std::fmt::Formatter;
-use std::fmt;
-use super::dice;
-
-#[derive(PartialEq, Debug, Clone, Copy)]
-pub enum ResultType {
-    Ones,
-    Twos,
-    Threes,
-    Fours,
-    Fives,
-    Sixes,
-    Pair,
-    TwoPairs,
-    ThreeOfAKind,
-    FourOfAKind,
-    SmallStraight,
-    LargeStraight,
-    FullHouse,
-    Chance,
-    Yatsy
-}
-
-impl Display for ResultType {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let name = match self {
-            ResultType::Ones => "Ones",
-            ResultType::Twos => "Twos",
-            ResultType::Threes => "Threes",
-            ResultType::Fours => "Fours",
-            ResultType::Fives => "Fives",
-            ResultType::Sixes => "Sixes",
-            ResultType::Pair => "Pair",
-            ResultType::TwoPairs => "Two Pairs",
-            ResultType::ThreeOfAKind => "Three of a Kind",
-            ResultType::FourOfAKind => "Four of a Kind",
-            ResultType::SmallStraight => "Small Straight",
-            ResultType::LargeStraight => "Large Straight",
-            ResultType::FullHouse => "Full House",
-            ResultType::Chance => "Chance",
-            ResultType::Yatsy => "Yatsy",
-        };
-        write!(f, "{}", name)
-    }
-}
-
-#[derive(PartialEq, Debug, Clone, Copy)]
-pub struct DiceResult {
-    pub result_type: ResultType,
-    pub score: i32
-}
-
-impl Display for DiceResult {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}p\t{}", self.score, self.result_type)
-    }
-}
-
-pub fn get_results(dice: dice::DiceRoll) -> Vec<DiceResult> {
-    let mut results = Vec::<DiceResult>::new();
-
-        let ones = dice.ones();
-        if ones > 0 {
-            results.push(DiceResult { result_type: ResultType::Ones, score: ones });
-        }
-
-        let twos = dice.twos();
-        if twos > 0 {
-            results.push(DiceResult { result_type: ResultType::Twos, score: twos });
-        }
-        
-        let threes = dice.threes();
-        if threes > 0 {
-            results.push(DiceResult { result_type: ResultType::Threes, score: threes });
-        }
-
-        let fours = dice.fours();
-        if fours > 0 {
-            results.push(DiceResult { result_type: ResultType::Fours, score: fours });
-        }
-
-        let fives = dice.fives();
-        if fives > 0 {
-            results.push(DiceResult { result_type: ResultType::Fives, score: fives });
-        }
-
-        let sixes = dice.sixes();
-        if sixes > 0 {
-            results.push(DiceResult { result_type: ResultType::Sixes, score: sixes });
-        }
-
-        let pair = dice.pair();
-        if pair > 0 {
-            results.push(DiceResult { result_type: ResultType::Pair, score: pair });
-        }
-
-        let two_pairs = dice.two_pairs();
-        if two_pairs > 0 {
-            results.push(DiceResult { result_type: ResultType::TwoPairs, score: two_pairs });
-        }
-
-        let three_of_a_kind = dice.three_of_a_kind();
-        if three_of_a_kind > 0 {
-            results.push(DiceResult { result_type: ResultType::ThreeOfAKind, score: three_of_a_kind });
-        }
-
-        let four_of_a_kind = dice.four_of_a_kind();
-        if four_of_a_kind > 0 {
-            results.push(DiceResult { result_type: ResultType::FourOfAKind, score: four_of_a_kind });
-        }
-
-        let small_straight = dice.small_straight();
-        if small_straight > 0 {
-            results.push(DiceResult { result_type: ResultType::SmallStraight, score: small_straight });
-        }
-
-        let large_straight = dice.large_straight();
-        if large_straight > 0 {
-            results.push(DiceResult { result_type: ResultType::LargeStraight, score: large_straight });
-        }
-
-        let full_house = dice.full_house();
-        if full_house > 0 {
-            results.push(DiceResult { result_type: ResultType::FullHouse, score: full_house });
-        }
-
-        let chance = dice.chance();
-        if chance > 0 {
-            results.push(DiceResult { result_type: ResultType::Chance, score: chance });
-        }
-
-        let yatsy = dice.yatsy();
-        if yatsy > 0 {
-            results.push(DiceResult { result_type: ResultType::Yatsy, score: yatsy });
-        }
-
-        results
-}
-
-#[cfg(test)]
-mod tests {
-    // Note this useful idiom: importing names from outer (for mod tests) scope.
-    use super::*;
-
-    #[test]
-    fn test_result() {
-        let results = get_results(dice::DiceRoll { dice: [1, 2, 3, 4, 5] });
-        assert_eq!(results, vec![
-            DiceResult { result_type: ResultType::Ones, score: 1 },
-            DiceResult { result_type: ResultType::Twos, score: 2 },
-            DiceResult { result_type: ResultType::Threes, score: 3 },
-            DiceResult { result_type: ResultType::Fours, score: 4 },
-            DiceResult { result_type: ResultType::Fives, score: 5 },
-            DiceResult { result_type: ResultType::SmallStraight, score: 15 },
-            DiceResult { result_type: ResultType::Chance, score: 15 },
-        ]);
-
-        let results = get_results(dice::DiceRoll { dice: [2, 2, 2, 3, 3] });
-        assert_eq!(results, vec![
-            DiceResult { result_type: ResultType::Twos, score: 6 },
-            DiceResult { result_type: ResultType::Threes, score: 6 },
-            DiceResult { result_type: ResultType::Pair, score: 6 },
-            DiceResult { result_type: ResultType::ThreeOfAKind, score: 6 },
-            DiceResult { result_type: ResultType::FullHouse, score: 12 },
-            DiceResult { result_type: ResultType::Chance, score: 12 },
-        ]);
-
-        let results = get_results(dice::DiceRoll { dice: [1, 1, 1, 1, 1] });
-        assert_eq!(results, vec![
-            DiceResult { result_type: ResultType::Ones, score: 5 },
-            DiceResult { result_type: ResultType::Pair, score: 2 },
-            DiceResult { result_type: ResultType::ThreeOfAKind, score: 3 },
-            DiceResult { result_type: ResultType::FourOfAKind, score: 4 },
-            DiceResult { result_type: ResultType::Chance, score: 5 },
-            DiceResult { result_type: ResultType::Yatsy, score: 50 },
-        ]);
-    }
-}
\ No newline at end of file
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+use super::dice;
+use super::ruleset;
+use super::scores;
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ResultType {
+    Ones,
+    Twos,
+    Threes,
+    Fours,
+    Fives,
+    Sixes,
+    Pair,
+    TwoPairs,
+    ThreePairs,
+    ThreeOfAKind,
+    FourOfAKind,
+    FiveOfAKind,
+    SmallStraight,
+    LargeStraight,
+    FullStraight,
+    FullHouse,
+    Castle,
+    Tower,
+    Chance,
+    Straight,
+    Yatsy,
+    DoubleYatsy,
+    // Categories of a custom ruleset, by index into its category list
+    Custom(u8)
+}
+
+impl Display for ResultType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            ResultType::Ones => "Ones",
+            ResultType::Twos => "Twos",
+            ResultType::Threes => "Threes",
+            ResultType::Fours => "Fours",
+            ResultType::Fives => "Fives",
+            ResultType::Sixes => "Sixes",
+            ResultType::Pair => "Pair",
+            ResultType::TwoPairs => "Two Pairs",
+            ResultType::ThreePairs => "Three Pairs",
+            ResultType::ThreeOfAKind => "Three of a Kind",
+            ResultType::FourOfAKind => "Four of a Kind",
+            ResultType::FiveOfAKind => "Five of a Kind",
+            ResultType::SmallStraight => "Small Straight",
+            ResultType::LargeStraight => "Large Straight",
+            ResultType::FullStraight => "Full Straight",
+            ResultType::FullHouse => "Full House",
+            ResultType::Castle => "Castle",
+            ResultType::Tower => "Tower",
+            ResultType::Chance => "Chance",
+            ResultType::Straight => "Straight",
+            ResultType::Yatsy => "Yatsy",
+            ResultType::DoubleYatsy => "Double Yatsy",
+            ResultType::Custom(index) => return write!(f, "Custom {}", index + 1),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// The German names used on a Kniffel score card
+pub struct KniffelName(pub ResultType);
+
+impl Display for KniffelName {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self.0 {
+            ResultType::Ones => "Einser",
+            ResultType::Twos => "Zweier",
+            ResultType::Threes => "Dreier",
+            ResultType::Fours => "Vierer",
+            ResultType::Fives => "Fünfer",
+            ResultType::Sixes => "Sechser",
+            ResultType::ThreeOfAKind => "Dreierpasch",
+            ResultType::FourOfAKind => "Viererpasch",
+            ResultType::FullHouse => "Full House",
+            ResultType::SmallStraight => "Kleine Straße",
+            ResultType::LargeStraight => "Große Straße",
+            ResultType::Yatsy => "Kniffel",
+            ResultType::Chance => "Chance",
+            result_type => return write!(f, "{}", result_type),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// The Spanish names used on a Generala score card
+pub struct GeneralaName(pub ResultType);
+
+impl Display for GeneralaName {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self.0 {
+            ResultType::Ones => "Unos",
+            ResultType::Twos => "Doses",
+            ResultType::Threes => "Treses",
+            ResultType::Fours => "Cuatros",
+            ResultType::Fives => "Cincos",
+            ResultType::Sixes => "Seises",
+            ResultType::Straight => "Escalera",
+            ResultType::FullHouse => "Full",
+            ResultType::FourOfAKind => "Póker",
+            ResultType::Yatsy => "Generala",
+            ResultType::DoubleYatsy => "Doble Generala",
+            result_type => return write!(f, "{}", result_type),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl ResultType {
+    // The upper section category counting the given die value
+    pub fn for_value(value: i32) -> Option<ResultType> {
+        match value {
+            1 => Some(ResultType::Ones),
+            2 => Some(ResultType::Twos),
+            3 => Some(ResultType::Threes),
+            4 => Some(ResultType::Fours),
+            5 => Some(ResultType::Fives),
+            6 => Some(ResultType::Sixes),
+            _ => None
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DiceResult {
+    pub result_type: ResultType,
+    pub score: i32
+}
+
+impl Display for DiceResult {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}p\t{}", self.score, self.result_type)
+    }
+}
+
+// The results for the dice after `rerolls_used` rerolls in the turn
+pub fn get_results(ruleset: &dyn ruleset::Ruleset, dice: &dice::DiceRoll, rerolls_used: i32) -> Vec<DiceResult> {
+    ruleset.categories().into_iter()
+        .map(|result_type| DiceResult { result_type, score: ruleset.score_turn(result_type, dice, rerolls_used) })
+        .filter(|r| r.score > 0)
+        .collect()
+}
+
+// The results that can actually be placed on the card, which for some rules
+// depends on what has already been scored
+pub fn get_available_results(ruleset: &dyn ruleset::Ruleset, dice: &dice::DiceRoll, rerolls_used: i32, card: &scores::ScoreCard) -> Vec<DiceResult> {
+    ruleset.open_types(dice, card).into_iter()
+        .map(|result_type| DiceResult { result_type, score: ruleset.score_on_card(result_type, dice, rerolls_used, card) })
+        .filter(|r| r.score > 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_result() {
+        let results = get_results(&ruleset::Scandinavian, &dice::DiceRoll { dice: vec![1, 2, 3, 4, 5], faces: 6 }, 0);
+        assert_eq!(results, vec![
+            DiceResult { result_type: ResultType::Ones, score: 1 },
+            DiceResult { result_type: ResultType::Twos, score: 2 },
+            DiceResult { result_type: ResultType::Threes, score: 3 },
+            DiceResult { result_type: ResultType::Fours, score: 4 },
+            DiceResult { result_type: ResultType::Fives, score: 5 },
+            DiceResult { result_type: ResultType::SmallStraight, score: 15 },
+            DiceResult { result_type: ResultType::Chance, score: 15 },
+        ]);
+
+        let results = get_results(&ruleset::Scandinavian, &dice::DiceRoll { dice: vec![2, 2, 2, 3, 3], faces: 6 }, 0);
+        assert_eq!(results, vec![
+            DiceResult { result_type: ResultType::Twos, score: 6 },
+            DiceResult { result_type: ResultType::Threes, score: 6 },
+            DiceResult { result_type: ResultType::Pair, score: 6 },
+            DiceResult { result_type: ResultType::TwoPairs, score: 10 },
+            DiceResult { result_type: ResultType::ThreeOfAKind, score: 6 },
+            DiceResult { result_type: ResultType::FullHouse, score: 12 },
+            DiceResult { result_type: ResultType::Chance, score: 12 },
+        ]);
+
+        let results = get_results(&ruleset::Scandinavian, &dice::DiceRoll { dice: vec![1, 1, 1, 1, 1], faces: 6 }, 0);
+        assert_eq!(results, vec![
+            DiceResult { result_type: ResultType::Ones, score: 5 },
+            DiceResult { result_type: ResultType::Pair, score: 2 },
+            DiceResult { result_type: ResultType::ThreeOfAKind, score: 3 },
+            DiceResult { result_type: ResultType::FourOfAKind, score: 4 },
+            DiceResult { result_type: ResultType::Chance, score: 5 },
+            DiceResult { result_type: ResultType::Yatsy, score: 50 },
+        ]);
+
+        let results = get_results(&ruleset::Maxi, &dice::DiceRoll { dice: vec![3, 3, 3, 3, 3, 3], faces: 6 }, 0);
+        assert_eq!(results, vec![
+            DiceResult { result_type: ResultType::Threes, score: 18 },
+            DiceResult { result_type: ResultType::Pair, score: 6 },
+            DiceResult { result_type: ResultType::ThreeOfAKind, score: 9 },
+            DiceResult { result_type: ResultType::FourOfAKind, score: 12 },
+            DiceResult { result_type: ResultType::FiveOfAKind, score: 15 },
+            DiceResult { result_type: ResultType::Chance, score: 18 },
+            DiceResult { result_type: ResultType::Yatsy, score: 100 },
+        ]);
+    }
+}
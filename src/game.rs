@@ -1,172 +1,909 @@
-use std::process::exit;
-
-use super::dice;
-use super::input;
-use super::dice_result;
-use super::scores;
-
-pub struct Game {
-    pub rerolls: i32,
-    pub dice: dice::DiceRoll,
-    pub score_card: scores::ScoreCard
-}
-
-impl Game {
-    pub fn new() -> Game {
-        Game {
-            rerolls: 2,
-            dice: dice::DiceRoll::new(),
-            score_card: scores::ScoreCard::new()
-        }
-    }
-
-    pub fn start(&mut self) {
-        self.print_welcome();
-        self.start_round();
-    }
-
-    pub fn print_welcome(&self) {
-        println!("\n--- Welcome to Command Line Yatsy!---");
-        self.print_help();
-    }
-
-    fn print_help(&self) {
-        println!("Enter \"r\" followed by indices to reroll dice. Indices are 1 based and separated by spaces.");
-        println!("Enter \"p\" to pick a result from the available options.");
-        println!("Enter \"s\" to show the current score card.");
-        println!("Enter \"q\" to quit or \"reset\" to start a new game.");
-        println!("Enter \"h\" to show this help message.")
-    }
-
-    fn print_state(&self) {
-        println!("\nDice: {}, Rerolls left: {}", self.dice, self.rerolls);
-    }
-
-    fn start_round(&mut self) {
-        self.check_end();
-        self.reset_round();
-        self.get_command();
-    }
-
-    fn check_end(&self) {
-        if self.score_card.is_complete() {
-            println!("\n--- Game over! Thanks for playing! ---");
-            exit(0);
-        }
-    }
-
-    fn reset_round(&mut self) {
-        self.rerolls = 2;
-        self.dice = dice::DiceRoll::new();
-    }
-
-    fn reset_game(&mut self) {
-        // Reset terminal
-        print!("{}[2J", 27 as char);
-
-        self.score_card = scores::ScoreCard::new();
-        self.reset_round();
-
-        self.start();
-    }
-
-    fn get_command(&mut self) {
-        self.print_state();
-
-        match input::get_command() {
-            input::Command::Reroll(indices) => self.handle_reroll(indices),
-            input::Command::Pick => self.handle_pick(),
-            input::Command::Quit => exit(0),
-            input::Command::Reset => self.reset_game(),
-            input::Command::ShowScores => {
-                self.score_card.print_scores();
-                self.get_command();
-            }
-            input::Command::Help => {
-                self.print_help();
-                self.get_command();
-            },
-        }
-    }
-
-    fn handle_pick(&mut self) {
-        let available_types = self.score_card.get_available_types();
-        let results = dice_result::get_results( self.dice.clone() );
-        let available_results = results.iter().filter(|&&r| available_types.contains(&r.result_type)).collect::<Vec<_>>();
-        if available_results.len() == 0 {
-            self.handle_strike();
-        }
-        
-        println!("Pick a result:");
-        for i in 0..available_results.len() {
-            println!("{}: {}", i + 1, available_results[i]);
-        }
-        println!("{}: Strike row", available_results.len() + 1);
-
-        let pick = input::get_pick();
-        if pick > available_results.len() {
-            println!("Invalid selection. Try again.");
-            self.handle_pick();
-        }
-
-        if pick == available_results.len() {
-            self.handle_strike();
-            return;
-        }
-
-        self.score_card.add_result(available_results[pick].clone());
-
-        self.score_card.print_scores();
-        self.start_round();
-    }
-
-    fn handle_strike(&mut self) {
-        println!("Strike a result:");
-        let available_types = self.score_card.get_available_types();
-        for i in 0..available_types.len() {
-            println!("{}: {}", i + 1, available_types[i]);
-        }
-
-        let index = input::get_pick();
-        if index >= available_types.len() {
-            println!("Invalid selection. Try again.");
-            self.handle_strike();
-        }
-
-        self.score_card.strike(available_types[index]);
-
-        self.score_card.print_scores();
-        self.start_round();
-    }
-
-    fn handle_reroll(&mut self, indices: Vec<usize>) {
-        match self.get_reroll(indices) {
-            Ok(indices) => {
-                self.rerolls = self.rerolls - 1;
-                self.dice = self.dice.reroll(indices);
-            },
-            Err(e) => {
-                println!("{}", e);
-            }
-        }
-        
-        if self.rerolls > 0 {
-            self.get_command();
-        } else {
-            self.print_state();
-            self.handle_pick();
-        }
-    }
-
-    fn get_reroll(&self, indices: Vec<usize>) -> Result<Vec<usize>, String> {
-        if self.rerolls == 0 {
-            return Err("You have no rerolls left!".to_string())
-        }
-
-        if (indices.len() == 0) || (indices.len() > 5) {
-            return Err("You must reroll between 1 and 5 dice!".to_string())
-        }
-
-        return Ok(indices);
-    }
-}
\ No newline at end of file
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::dice;
+use super::input;
+use super::odds;
+use super::dice_result;
+use super::ruleset;
+use super::scores;
+use super::solver;
+use super::strategy::{self, Strategy};
+
+pub struct Player {
+    pub name: String,
+    pub score_card: scores::ScoreCard,
+    pub saved_rerolls: i32,
+    // Set for computer players, who play their turns without input
+    pub bot: Option<Box<dyn Strategy + Send>>,
+    // Every turn's dice come from this player's own stream
+    rng: StdRng
+}
+
+impl Player {
+    fn new(name: String, score_card: scores::ScoreCard) -> Player {
+        Player { name, score_card, saved_rerolls: 0, bot: None, rng: StdRng::from_entropy() }
+    }
+}
+
+// How a player fills in a row at the end of a turn
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Pick {
+    Result(dice_result::ResultType),
+    Zero(dice_result::ResultType),
+    Strike(dice_result::ResultType)
+}
+
+impl Pick {
+    pub fn result_type(&self) -> dice_result::ResultType {
+        match *self {
+            Pick::Result(result_type) | Pick::Zero(result_type) | Pick::Strike(result_type) => result_type
+        }
+    }
+}
+
+// The name, or the name with the lowest number after it that isn't taken yet
+pub fn unique_name(name: &str, taken: &[String]) -> String {
+    (1..).map(|n| if n == 1 { name.to_string() } else { format!("{} {}", name, n) })
+        .find(|name| !taken.contains(name))
+        .unwrap()
+}
+
+// A choice made during a game, with the dice and card it was made with
+#[derive(Clone)]
+pub struct Decision {
+    pub player: usize,
+    pub dice: dice::DiceRoll,
+    // Rerolls left when the choice was made
+    pub rerolls: i32,
+    pub card: scores::ScoreCard,
+    pub action: Action
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Action {
+    Reroll(Vec<usize>),
+    Pick(Pick, usize)
+}
+
+// Player names from a comma separated list
+pub fn parse_players(names: &str) -> Result<Vec<String>, String> {
+    let players = names.split(',').map(|n| n.trim().to_string()).collect::<Vec<_>>();
+    if players.len() > 8 || players.iter().any(|n| n.is_empty()) {
+        return Err("Give between 1 and 8 player names, separated by commas.".to_string());
+    }
+    if players.iter().any(|n| players.iter().filter(|&m| m == n).count() > 1) {
+        return Err("Player names must be unique.".to_string());
+    }
+    Ok(players)
+}
+
+pub struct Game {
+    pub rerolls: i32,
+    pub rerolls_used: i32,
+    pub dice: dice::DiceRoll,
+    pub players: Vec<Player>,
+    pub current: usize,
+    // Set when a player wins outright, like with a served Generala
+    pub winner: Option<usize>,
+    pub ruleset: Arc<dyn ruleset::Ruleset>,
+    pub console: input::Console,
+    seed: Option<u64>,
+    // Everyone plays the same dice from the seed, one whole card at a time
+    duplicate: bool,
+    turn_rng: StdRng,
+    // Every reroll and pick since the game started, for reviewing it afterwards
+    history: Vec<Decision>,
+    // Loaded on the first hint
+    solver: Option<solver::Solver>,
+    // Set while the odds are shown
+    odds: Option<odds::Odds>
+}
+
+impl Game {
+    pub fn new(ruleset: Arc<dyn ruleset::Ruleset>, forced: bool, names: Vec<String>) -> Game {
+        Game::with_console(ruleset, forced, names, input::Console::stdio())
+    }
+
+    pub fn with_console(ruleset: Arc<dyn ruleset::Ruleset>, forced: bool, names: Vec<String>, console: input::Console) -> Game {
+        let players = names.into_iter()
+            .map(|name| Player::new(name, Game::new_score_card(ruleset.clone(), forced)))
+            .collect();
+
+        let mut game = Game {
+            rerolls: 0,
+            rerolls_used: 0,
+            dice: dice::DiceRoll::new(ruleset.dice_count(), ruleset.faces()),
+            players,
+            current: 0,
+            winner: None,
+            ruleset,
+            console,
+            seed: None,
+            duplicate: false,
+            turn_rng: StdRng::from_entropy(),
+            history: Vec::new(),
+            solver: None,
+            odds: None
+        };
+        game.start_turn();
+        game
+    }
+
+    // Rolls all dice from the seed. In duplicate mode every player gets the
+    // same stream, so scores measure the choices rather than the luck.
+    pub fn with_seed(mut self, seed: u64, duplicate: bool) -> Game {
+        self.seed = Some(seed);
+        self.duplicate = duplicate;
+        self.reset();
+        self
+    }
+
+    // Seats a computer player after the others, named after its strategy.
+    // The game starts over so the bot gets its own dice stream.
+    pub fn add_bot(&mut self, bot: Box<dyn Strategy + Send>) {
+        let taken = self.players.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+        let name = unique_name(&bot.name(), &taken);
+        let forced = self.score_card().is_forced();
+        let mut player = Player::new(name, Game::new_score_card(self.ruleset.clone(), forced));
+        player.bot = Some(bot);
+        self.players.push(player);
+        self.reset();
+    }
+
+    pub fn is_duplicate(&self) -> bool {
+        self.duplicate
+    }
+
+    fn seed_players(&mut self) {
+        let seed = self.seed.unwrap_or_else(|| rand::thread_rng().r#gen());
+        for (i, player) in self.players.iter_mut().enumerate() {
+            let offset = if self.duplicate { 0 } else { i as u64 };
+            player.rng = StdRng::seed_from_u64(seed.wrapping_add(offset));
+        }
+    }
+
+    fn new_score_card(ruleset: Arc<dyn ruleset::Ruleset>, forced: bool) -> scores::ScoreCard {
+        if forced {
+            scores::ScoreCard::new_forced(ruleset)
+        } else {
+            scores::ScoreCard::new(ruleset)
+        }
+    }
+
+    pub fn current_player(&self) -> &Player {
+        &self.players[self.current]
+    }
+
+    pub fn score_card(&self) -> &scores::ScoreCard {
+        &self.current_player().score_card
+    }
+
+    pub fn is_multiplayer(&self) -> bool {
+        self.players.len() > 1
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.winner.is_some() || self.players.iter().all(|p| p.score_card.is_complete())
+    }
+
+    // The results the current dice can be placed as
+    pub fn available_results(&self) -> Vec<dice_result::DiceResult> {
+        dice_result::get_available_results(self.ruleset.as_ref(), &self.dice, self.rerolls_used, self.score_card())
+    }
+
+    // The rows that can be scored as zero or struck with the current dice
+    pub fn open_types(&self) -> Vec<dice_result::ResultType> {
+        self.ruleset.open_types(&self.dice, self.score_card())
+    }
+
+    pub fn history(&self) -> &[Decision] {
+        &self.history
+    }
+
+    pub fn reroll(&mut self, indices: Vec<usize>) -> Result<(), String> {
+        let indices = self.get_reroll(indices)?;
+        self.record(Action::Reroll(indices.clone()));
+        self.rerolls -= 1;
+        self.rerolls_used += 1;
+        self.dice = self.dice.reroll_with(indices, &mut self.turn_rng);
+        self.check_instant_win();
+        Ok(())
+    }
+
+    // Fills in a row of the current player's card and moves on to the next turn
+    pub fn place(&mut self, pick: Pick, column: usize) -> Result<(), String> {
+        let result = self.check_pick(pick, column)?;
+        let result_type = pick.result_type();
+
+        self.record(Action::Pick(pick, column));
+        let extra_bonus = self.ruleset.extra_bonus(&self.dice, self.score_card());
+        let player = &mut self.players[self.current];
+        player.score_card.add_extra_bonus(extra_bonus);
+        match pick {
+            Pick::Strike(_) => player.score_card.strike(result_type, column),
+            _ => player.score_card.add_result(result, column)
+        }
+
+        self.end_turn();
+        Ok(())
+    }
+
+    // The result a pick fills in, or why it can't be made
+    fn check_pick(&self, pick: Pick, column: usize) -> Result<dice_result::DiceResult, String> {
+        if self.is_over() {
+            return Err("The game is over!".to_string())
+        }
+
+        let result_type = pick.result_type();
+        let result = match pick {
+            Pick::Result(_) => self.available_results().into_iter()
+                .find(|r| r.result_type == result_type)
+                .ok_or(format!("The dice can't be scored as {}!", self.ruleset.category_name(result_type)))?,
+            Pick::Zero(_) | Pick::Strike(_) => {
+                if !self.open_types().contains(&result_type) {
+                    return Err(format!("{} can't be filled in!", self.ruleset.category_name(result_type)))
+                }
+                dice_result::DiceResult { result_type, score: 0 }
+            }
+        };
+
+        if !self.score_card().get_open_columns(result_type).contains(&column) {
+            return Err(format!("Column {} is not open for {}!", column + 1, self.ruleset.category_name(result_type)))
+        }
+        Ok(result)
+    }
+
+    fn record(&mut self, action: Action) {
+        self.history.push(Decision {
+            player: self.current,
+            dice: self.dice.clone(),
+            rerolls: self.rerolls,
+            card: self.score_card().clone(),
+            action
+        });
+    }
+
+    // Players ordered by final score, with their rank. Tied players share a rank.
+    pub fn ranking(&self) -> Vec<(usize, &Player)> {
+        let mut players = self.players.iter().enumerate().collect::<Vec<_>>();
+        players.sort_by_key(|&(i, p)| (self.winner != Some(i), -p.score_card.get_total()));
+
+        let mut ranking = Vec::<(usize, &Player)>::new();
+        for (position, &(i, player)) in players.iter().enumerate() {
+            // An instant winner is ranked alone, whatever the points
+            let rank = match ranking.last() {
+                Some(&(rank, last)) if self.winner != Some(i) && self.winner != Some(players[position - 1].0)
+                    && last.score_card.get_total() == player.score_card.get_total() => rank,
+                _ => position + 1
+            };
+            ranking.push((rank, player));
+        }
+        ranking
+    }
+
+    pub fn reset(&mut self) {
+        for player in self.players.iter_mut() {
+            player.score_card.reset();
+            player.saved_rerolls = 0;
+        }
+        self.seed_players();
+        self.history.clear();
+        self.current = 0;
+        self.winner = None;
+        self.start_turn();
+    }
+
+    fn start_turn(&mut self) {
+        let player = &mut self.players[self.current];
+        self.rerolls = self.ruleset.rerolls_per_turn();
+        if self.ruleset.saves_rerolls() {
+            self.rerolls += player.saved_rerolls;
+        }
+        self.rerolls_used = 0;
+        // One draw from the player's stream per turn keeps the streams in step,
+        // however many dice were rerolled
+        self.turn_rng = StdRng::seed_from_u64(player.rng.next_u64());
+        self.dice = dice::DiceRoll::roll(self.ruleset.dice_count(), self.ruleset.faces(), &mut self.turn_rng);
+        self.check_instant_win();
+    }
+
+    fn end_turn(&mut self) {
+        self.players[self.current].saved_rerolls = self.rerolls;
+        if !self.duplicate || self.score_card().is_complete() {
+            self.current = (self.current + 1) % self.players.len();
+        }
+        self.start_turn();
+    }
+
+    // Duplicate games have no instant wins, the others haven't had their turn yet
+    fn check_instant_win(&mut self) {
+        if self.winner.is_none() && !self.duplicate && self.ruleset.is_instant_win(&self.dice, self.rerolls_used) {
+            self.winner = Some(self.current);
+        }
+    }
+
+    // Plays until the game is over or the player quits
+    pub fn start(&mut self) {
+        self.print_welcome();
+        while !self.is_over() {
+            if !self.play_turn() {
+                return;
+            }
+        }
+        self.print_game_over();
+    }
+
+    pub fn print_welcome(&mut self) {
+        self.console.print("\n--- Welcome to Command Line Yatsy!---");
+        if self.score_card().is_forced() {
+            self.console.print(format!("Playing forced {}, fill the score card from top to bottom", self.ruleset.name()));
+        } else {
+            self.console.print(format!("Playing {}", self.ruleset.name()));
+        }
+        if self.is_multiplayer() {
+            let names = self.players.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+            self.console.print(format!("Players: {}", names.join(", ")));
+        }
+        if self.duplicate {
+            self.console.print("Duplicate game: everyone gets the same dice, each player fills a whole card in turn");
+        }
+        if let Some(seed) = self.seed {
+            self.console.print(format!("Seed: {}", seed));
+        }
+        self.print_help();
+    }
+
+    fn print_help(&mut self) {
+        self.console.print("Enter \"r\" followed by indices to reroll dice. Indices are 1 based and separated by spaces.");
+        self.console.print("Enter \"p\" to pick a result from the available options.");
+        self.console.print("Enter \"s\" to show the current score card.");
+        if self.is_multiplayer() {
+            self.console.print("Enter \"b\" to show the scoreboard for all players.");
+        }
+        self.console.print("Enter \"?\" for a hint on what to hold and pick, also when picking a result.");
+        self.console.print("Enter \"o\" to show or hide the odds of every open row while you have rerolls left.");
+        self.console.print("Enter \"q\" to quit or \"reset\" to start a new game.");
+        self.console.print("Enter \"h\" to show this help message.")
+    }
+
+    fn print_state(&mut self) {
+        self.console.print(format!("\nDice: {}, Rerolls left: {}", self.dice, self.rerolls));
+        if let Some(odds) = &self.odds && self.rerolls > 0 {
+            let mut lines = vec!["Odds\tExpected\tGoing for".to_string()];
+            for (result_type, chance, expected) in odds.category_odds(&self.dice, self.rerolls, &self.score_card().get_available_types()) {
+                lines.push(format!("{:.1}%\t{:.1}p\t\t{}", chance * 100.0, expected, self.ruleset.category_name(result_type)));
+            }
+            self.console.print(lines.join("\n"));
+        }
+    }
+
+    fn toggle_odds(&mut self) {
+        if self.odds.take().is_some() {
+            self.console.print("Odds hidden");
+            return;
+        }
+        match odds::Odds::new(self.ruleset.clone()) {
+            Ok(odds) => {
+                self.odds = Some(odds);
+                self.console.print("Odds shown while you have rerolls left, holding the best dice for each row");
+            },
+            Err(e) => self.console.print(e),
+        }
+    }
+
+    fn print_game_over(&mut self) {
+        if let Some(winner) = self.winner {
+            self.print_state();
+            self.console.print(format!("\n{} on the first roll!", self.ruleset.category_name(dice_result::ResultType::Yatsy)));
+            if self.is_multiplayer() {
+                self.console.print(format!("{} wins the game!", self.players[winner].name));
+            } else {
+                self.console.print("You win!");
+            }
+        }
+
+        self.console.print("\n--- Game over! Thanks for playing! ---");
+        if self.is_multiplayer() {
+            self.print_scoreboard();
+            self.console.print("\n--- Final ranking ---");
+            let ranking = self.ranking().into_iter()
+                .map(|(rank, player)| format!("{}. {}\t{}p", rank, player.name, player.score_card.get_total()))
+                .collect::<Vec<_>>();
+            self.console.print(ranking.join("\n"));
+        }
+    }
+
+    fn print_scoreboard(&mut self) {
+        let names = self.players.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+        self.console.print(scores::format_board(&names, &self.scoreboard()));
+    }
+
+    // All players' cards side by side, as rows of (label, cell per player).
+    // Multi-column cards show the category totals.
+    pub fn scoreboard(&self) -> Vec<(String, Vec<String>)> {
+        let mut rows = Vec::<(String, Vec<String>)>::new();
+        for result_type in self.ruleset.categories() {
+            let cells = self.players.iter().map(|p| {
+                if self.ruleset.columns() == 1 {
+                    p.score_card.format_cell(result_type, 0)
+                } else {
+                    format!("{}p", p.score_card.get_category_total(result_type))
+                }
+            });
+            rows.push((self.ruleset.category_name(result_type), cells.collect()));
+        }
+        rows.push(("Bonus".to_string(), self.players.iter().map(|p| format!("{}p", p.score_card.get_bonus_total())).collect()));
+        rows.push(("Total".to_string(), self.players.iter().map(|p| format!("{}p", p.score_card.get_total())).collect()));
+        rows
+    }
+
+    // Returns false when the player quits
+    fn play_turn(&mut self) -> bool {
+        if self.is_multiplayer() {
+            self.console.print(format!("\n--- {}'s turn ---", self.current_player().name));
+        }
+
+        let player = self.current;
+        if self.players[player].bot.is_some() {
+            self.play_bot_turn();
+            return true;
+        }
+
+        while self.current == player && !self.is_over() {
+            self.print_state();
+
+            match self.console.get_command() {
+                input::Command::Reroll(indices) => self.handle_reroll(indices),
+                input::Command::Pick => self.handle_pick(),
+                input::Command::Quit => return false,
+                input::Command::Reset => {
+                    self.reset_game();
+                    return true;
+                },
+                input::Command::ShowScores => self.console.print(self.players[player].score_card.format_scores()),
+                input::Command::ShowBoard => self.print_scoreboard(),
+                input::Command::Help => self.print_help(),
+                input::Command::Hint => self.print_hint(),
+                input::Command::Odds => self.toggle_odds(),
+            }
+        }
+        true
+    }
+
+    // Shows the bot's choices as the commands a player would have typed
+    fn play_bot_turn(&mut self) {
+        let player = self.current;
+        let mut bot = self.players[player].bot.take().unwrap();
+        let name = self.players[player].name.clone();
+
+        self.print_state();
+        while self.rerolls > 0 {
+            let held = bot.choose_keep(&strategy::State::new(self));
+            let indices = (0..self.dice.dice.len()).filter(|i| !held.contains(i)).collect::<Vec<_>>();
+            if indices.is_empty() {
+                break;
+            }
+            let typed = indices.iter().map(|i| (i + 1).to_string()).collect::<Vec<_>>();
+            self.console.print(format!("{}> r {}", name, typed.join(" ")));
+            if let Err(e) = self.reroll(indices) {
+                self.console.print(e);
+                break;
+            }
+            if self.is_over() {
+                self.players[player].bot = Some(bot);
+                return;
+            }
+            self.print_state();
+        }
+
+        let (mut pick, mut column) = bot.choose_category(&strategy::State::new(self));
+        self.players[player].bot = Some(bot);
+        // The bot would be asked the same thing again, so a pick that can't be made strikes the first open row
+        if let Err(e) = self.check_pick(pick, column) {
+            self.console.print(format!("{} can't pick that: {}", name, e));
+            let result_type = self.open_types()[0];
+            pick = Pick::Strike(result_type);
+            column = self.score_card().get_open_columns(result_type)[0];
+        }
+        self.console.print(format!("{}> p", name));
+        let category = self.ruleset.category_name(pick.result_type());
+        match pick {
+            Pick::Result(result_type) => {
+                let score = self.available_results().iter().find(|r| r.result_type == result_type).map_or(0, |r| r.score);
+                self.console.print(format!("Picked {}p {}", score * self.ruleset.column_multiplier(column), category));
+            },
+            Pick::Zero(_) => self.console.print(format!("Scored 0 in {}", category)),
+            Pick::Strike(_) => self.console.print(format!("Struck {}", category)),
+        }
+        self.finish_pick(pick, column);
+    }
+
+    fn reset_game(&mut self) {
+        // Reset terminal
+        self.console.print(format!("{}[2J", 27 as char));
+
+        self.reset();
+        self.print_welcome();
+    }
+
+    // The pick handlers give up when the input is closed, the next command will quit
+    fn handle_pick(&mut self) {
+        // With nothing to score there's still the choice between a zero and a strike
+        let available_results = self.available_results();
+        self.console.print("Pick a result:");
+        for (i, result) in available_results.iter().enumerate() {
+            self.console.print(format!("{}: {}p\t{}", i + 1, result.score, self.ruleset.category_name(result.result_type)));
+        }
+        self.console.print(format!("{}: Score 0 in a row", available_results.len() + 1));
+        self.console.print(format!("{}: Strike row", available_results.len() + 2));
+
+        let pick = loop {
+            let Some(pick) = self.get_pick_or_hint() else {
+                return;
+            };
+            if pick <= available_results.len() + 1 {
+                break pick;
+            }
+            self.console.print("Invalid selection. Try again.");
+        };
+
+        if pick == available_results.len() {
+            self.handle_zero();
+            return;
+        }
+
+        if pick == available_results.len() + 1 {
+            self.handle_strike();
+            return;
+        }
+
+        let result = available_results[pick];
+        if let Some(column) = self.pick_column(result.result_type, Some(result.score)) {
+            self.finish_pick(Pick::Result(result.result_type), column);
+        }
+    }
+
+    // Like Console::get_pick, but shows a hint on "?"
+    fn get_pick_or_hint(&mut self) -> Option<usize> {
+        loop {
+            let input = self.console.get_input("")?;
+            if let Some(input::Command::Hint) = input::parse_command(&input) {
+                self.print_hint();
+                continue;
+            }
+            match input.parse::<usize>() {
+                Ok(n) if n > 0 => return Some(n - 1),
+                _ => self.console.print("Invalid input. Try again."),
+            }
+        }
+    }
+
+    // The best dice to hold and the picks ranked by what they cost compared to the best
+    fn print_hint(&mut self) {
+        if self.solver.is_none() {
+            match solver::Solver::cached(self.ruleset.clone(), self.score_card().is_forced()) {
+                Ok(solver) => self.solver = Some(solver),
+                Err(e) => {
+                    self.console.print(e);
+                    return;
+                }
+            }
+            self.console.print("Working out the best strategy, this can take a minute the first time...");
+        }
+
+        let results = self.available_results();
+        let solver = self.solver.as_mut().unwrap();
+        let card = &self.players[self.current].score_card;
+        let mut lines = vec!["\n--- Hint ---".to_string()];
+
+        if self.rerolls > 0 {
+            for (held, expected) in solver.keep_values(&self.dice, self.rerolls, card).into_iter().take(3) {
+                lines.push(format!("{:.1}p expected\t{}", expected, self.dice.format_hold(&held)));
+            }
+            lines.push("If you pick now:".to_string());
+        }
+
+        let picks = solver.category_values(&self.dice, card);
+        let best = picks.first().map_or(0.0, |&(_, expected)| expected);
+        for (result_type, expected) in picks {
+            let score = results.iter().find(|r| r.result_type == result_type).map_or(0, |r| r.score);
+            let cost = if best - expected < 0.05 { "best".to_string() } else { format!("-{:.1}", best - expected) };
+            lines.push(format!("{:.1}p expected\t{}\t{}p\t{}", expected, cost, score, self.ruleset.category_name(result_type)));
+        }
+        self.console.print(lines.join("\n"));
+
+        // The cache only saves time, so a failed write isn't worth bothering the player with
+        let _ = solver.save(&solver.cache_path());
+    }
+
+    fn handle_zero(&mut self) {
+        if let Some(result_type) = self.pick_open_type("Score 0 in a row:")
+            && let Some(column) = self.pick_column(result_type, None) {
+            self.finish_pick(Pick::Zero(result_type), column);
+        }
+    }
+
+    fn handle_strike(&mut self) {
+        if let Some(result_type) = self.pick_open_type("Strike a result:")
+            && let Some(column) = self.pick_column(result_type, None) {
+            self.finish_pick(Pick::Strike(result_type), column);
+        }
+    }
+
+    fn finish_pick(&mut self, pick: Pick, column: usize) {
+        let player = self.current;
+        if let Err(e) = self.place(pick, column) {
+            self.console.print(e);
+            return;
+        }
+
+        self.console.print(self.players[player].score_card.format_scores());
+        if self.is_multiplayer() && self.current == 0 && !self.is_over() {
+            self.print_scoreboard();
+        }
+    }
+
+    fn pick_open_type(&mut self, prompt: &str) -> Option<dice_result::ResultType> {
+        self.console.print(prompt);
+        let available_types = self.open_types();
+        for (i, result_type) in available_types.iter().enumerate() {
+            self.console.print(format!("{}: {}", i + 1, self.ruleset.category_name(*result_type)));
+        }
+
+        loop {
+            let index = self.console.get_pick()?;
+            if index < available_types.len() {
+                return Some(available_types[index]);
+            }
+            self.console.print("Invalid selection. Try again.");
+        }
+    }
+
+    // Asks which column to use when the open columns for the category have different multipliers
+    fn pick_column(&mut self, result_type: dice_result::ResultType, score: Option<i32>) -> Option<usize> {
+        let columns = self.score_card().get_open_columns(result_type);
+        let multipliers = columns.iter().map(|&c| self.ruleset.column_multiplier(c)).collect::<Vec<_>>();
+        if multipliers.iter().all(|&m| m == multipliers[0]) {
+            return Some(columns[0]);
+        }
+
+        self.console.print("Pick a column:");
+        for (i, multiplier) in multipliers.iter().enumerate() {
+            match score {
+                Some(score) => self.console.print(format!("{}: x{} ({}p)", i + 1, multiplier, score * multiplier)),
+                None => self.console.print(format!("{}: x{}", i + 1, multiplier)),
+            }
+        }
+
+        loop {
+            let pick = self.console.get_pick()?;
+            if pick < columns.len() {
+                return Some(columns[pick]);
+            }
+            self.console.print("Invalid selection. Try again.");
+        }
+    }
+
+    fn handle_reroll(&mut self, indices: Vec<usize>) {
+        if let Err(e) = self.reroll(indices) {
+            self.console.print(e);
+        }
+
+        if self.rerolls == 0 && !self.is_over() {
+            self.print_state();
+            self.handle_pick();
+        }
+    }
+
+    fn get_reroll(&self, indices: Vec<usize>) -> Result<Vec<usize>, String> {
+        if self.rerolls == 0 {
+            return Err("You have no rerolls left!".to_string())
+        }
+
+        let dice_count = self.dice.dice.len();
+        if indices.is_empty() || (indices.len() > dice_count) {
+            return Err(format!("You must reroll between 1 and {} dice!", dice_count))
+        }
+
+        if indices.iter().any(|&i| i >= dice_count) {
+            return Err(format!("Dice are numbered from 1 to {}!", dice_count))
+        }
+
+        Ok(indices)
+    }
+}
+
+// A finished game where every player scores their given points in Chance and
+// strikes the rest, for the tests of what's done with finished games
+#[cfg(test)]
+pub(crate) fn finished_game(names: Vec<String>, score: impl Fn(&str) -> i32) -> Game {
+    use ruleset::Ruleset;
+
+    let mut game = Game::new(Arc::new(ruleset::Scandinavian), false, names);
+    for player in game.players.iter_mut() {
+        for result_type in ruleset::Scandinavian.categories() {
+            match result_type {
+                dice_result::ResultType::Chance => player.score_card.add_result(dice_result::DiceResult { result_type, score: score(&player.name) }, 0),
+                _ => player.score_card.strike(result_type, 0),
+            }
+        }
+    }
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Write};
+    use std::sync::Mutex;
+
+    fn new_game(names: &[&str]) -> Game {
+        Game::new(Arc::new(ruleset::Scandinavian), false, names.iter().map(|n| n.to_string()).collect())
+    }
+
+    #[test]
+    fn test_turn_rotation() {
+        let mut game = new_game(&["Kim", "Anna"]);
+        assert_eq!(game.current_player().name, "Kim");
+
+        game.place(Pick::Strike(dice_result::ResultType::Yatsy), 0).unwrap();
+        assert_eq!(game.current_player().name, "Anna");
+        assert_eq!(game.rerolls, 2);
+
+        game.place(Pick::Zero(dice_result::ResultType::Chance), 0).unwrap();
+        assert_eq!(game.current_player().name, "Kim");
+        assert!(game.place(Pick::Strike(dice_result::ResultType::Yatsy), 0).is_err());
+    }
+
+    #[test]
+    fn test_reroll_validation() {
+        let mut game = new_game(&["Kim"]);
+        assert!(game.reroll(vec![]).is_err());
+        assert!(game.reroll(vec![5]).is_err());
+        assert!(game.reroll(vec![0, 4]).is_ok());
+        assert!(game.reroll(vec![1]).is_ok());
+        assert!(game.reroll(vec![1]).is_err());
+        assert_eq!(game.rerolls_used, 2);
+    }
+
+    #[test]
+    fn test_saved_rerolls() {
+        let mut game = Game::new(Arc::new(ruleset::Maxi), false, vec!["Kim".to_string()]);
+        assert_eq!(game.rerolls, 2);
+
+        game.place(Pick::Strike(dice_result::ResultType::Yatsy), 0).unwrap();
+        assert_eq!(game.rerolls, 4);
+        game.place(Pick::Strike(dice_result::ResultType::Chance), 0).unwrap();
+        assert_eq!(game.rerolls, 6);
+
+        // Rerolls used this turn aren't saved
+        game.reroll(vec![0]).unwrap();
+        game.place(Pick::Strike(dice_result::ResultType::Ones), 0).unwrap();
+        assert_eq!(game.rerolls, 7);
+    }
+
+    #[test]
+    fn test_duplicate_dice() {
+        let mut game = new_game(&["Kim", "Anna"]).with_seed(42, true);
+        let categories = game.ruleset.categories();
+
+        let mut rolls = Vec::new();
+        for &result_type in categories.iter() {
+            let first = game.dice.clone();
+            game.reroll(vec![0, 2]).unwrap();
+            rolls.push((first, game.dice.clone()));
+            game.place(Pick::Strike(result_type), 0).unwrap();
+        }
+
+        assert_eq!(game.current_player().name, "Anna");
+        for (&result_type, (first, rerolled)) in categories.iter().zip(rolls) {
+            assert_eq!(game.dice, first);
+            game.reroll(vec![0, 2]).unwrap();
+            assert_eq!(game.dice, rerolled);
+            game.place(Pick::Strike(result_type), 0).unwrap();
+        }
+        assert!(game.is_over());
+    }
+
+    // Collects what the game prints
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_hint() {
+        let output = Output::default();
+        let console = input::Console::new(io::empty(), output.clone());
+        let mut game = Game::with_console(Arc::new(ruleset::Scandinavian), false, vec!["Kim".to_string()], console);
+        let open = [dice_result::ResultType::Chance, dice_result::ResultType::Yatsy];
+        for result_type in game.ruleset.categories().into_iter().filter(|t| !open.contains(t)) {
+            game.place(Pick::Strike(result_type), 0).unwrap();
+        }
+        game.dice = dice::DiceRoll { dice: vec![6, 6, 6, 6, 2], faces: 6 };
+        game.print_hint();
+
+        let text = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let hint = text.split("--- Hint ---\n").nth(1).unwrap().lines().collect::<Vec<_>>();
+        assert!(hint[0].ends_with("\tHold 6 6 6 6"));
+        let picks = &hint[hint.iter().position(|&l| l == "If you pick now:").unwrap() + 1..];
+        assert_eq!(picks.len(), 2);
+        assert!(picks[0].contains("\tbest\t26p\tChance"));
+        assert!(picks[1].contains("\t-") && picks[1].ends_with("\t0p\tYatsy"));
+    }
+
+    // Holds everything and always goes for Yatsy
+    struct Stubborn;
+
+    impl Strategy for Stubborn {
+        fn name(&self) -> String {
+            "Stubborn".to_string()
+        }
+
+        fn choose_keep(&mut self, state: &strategy::State) -> Vec<usize> {
+            (0..state.dice.dice.len()).collect()
+        }
+
+        fn choose_category(&mut self, _state: &strategy::State) -> (Pick, usize) {
+            (Pick::Result(dice_result::ResultType::Yatsy), 0)
+        }
+    }
+
+    #[test]
+    fn test_bot_invalid_pick() {
+        let console = input::Console::new(io::empty(), io::sink());
+        let mut game = Game::with_console(Arc::new(ruleset::Scandinavian), false, vec!["Kim".to_string()], console);
+        game.players[0].bot = Some(Box::new(Stubborn));
+        game.start();
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn test_pick_zero_without_results() {
+        let console = input::Console::new(io::Cursor::new("1\n1\n"), io::sink());
+        let mut game = Game::with_console(Arc::new(ruleset::Scandinavian), false, vec!["Kim".to_string()], console);
+        let open = [dice_result::ResultType::Ones, dice_result::ResultType::Yatsy];
+        for result_type in game.ruleset.categories().into_iter().filter(|t| !open.contains(t)) {
+            game.place(Pick::Strike(result_type), 0).unwrap();
+        }
+        game.dice = dice::DiceRoll { dice: vec![2, 3, 4, 5, 6], faces: 6 };
+        assert!(game.available_results().is_empty());
+
+        game.handle_pick();
+        let last = game.history().last().unwrap();
+        assert_eq!(last.action, Action::Pick(Pick::Zero(dice_result::ResultType::Ones), 0));
+    }
+
+    #[test]
+    fn test_ranking_ties() {
+        let mut game = new_game(&["Kim", "Anna", "Bob"]);
+        for (player, score) in [(0, 20), (1, 30), (2, 20)] {
+            let result = dice_result::DiceResult { result_type: dice_result::ResultType::Chance, score };
+            game.players[player].score_card.add_result(result, 0);
+        }
+
+        let ranking = game.ranking().iter().map(|(rank, p)| (*rank, p.name.as_str())).collect::<Vec<_>>();
+        assert_eq!(ranking, vec![(1, "Anna"), (2, "Kim"), (2, "Bob")]);
+    }
+
+    #[test]
+    fn test_ranking_instant_winner() {
+        let mut game = new_game(&["Kim", "Anna"]);
+        game.winner = Some(1);
+
+        let ranking = game.ranking().iter().map(|(rank, p)| (*rank, p.name.as_str())).collect::<Vec<_>>();
+        assert_eq!(ranking, vec![(1, "Anna"), (2, "Kim")]);
+    }
+}
@@ -1,63 +1,115 @@
-use std::io;
-use regex::Regex;
-use itertools::Itertools;
-
-pub enum Command {
-    Reroll(Vec<usize>),
-    Pick,
-    Quit,
-    Help,
-    Reset,
-    ShowScores
-}
-
-pub fn get_command() -> Command {
-    loop {
-        let input = get_input("");
-
-        // Matches 'r' followed by spaces, then numbers with spaces preserved
-        let re = Regex::new(r"^r\s+([\d\s]+)$").unwrap(); 
-        if let Some(caps) = re.captures(&input) {
-            let indices = caps.get(1).unwrap().as_str();
-            let numbers = indices.split_whitespace()
-            .map(|s| s.parse::<usize>())
-            .filter_map(Result::ok)
-            .filter(|&n| (1..6).contains(&n))
-            .map(|n| n - 1)
-            .unique()
-            .collect();
-            return Command::Reroll(numbers);
-        }
-
-        match input.as_str() {
-            "p" | "pick" => return Command::Pick,
-            "q" | "quit" => return Command::Quit,
-            "h" | "help" => return Command::Help,
-            "s" | "scores" => return Command::ShowScores,
-            "reset" => return Command::Reset,
-            _ => ()
-        }
-
-        println!("Invalid command. Try again.");
-    }
-}
-
-pub fn get_pick() -> usize {
-    loop {
-        let input = get_input("");
-        match input.parse::<usize>() {
-            Ok(n) => return n - 1,
-            Err(_) => println!("Invalid input. Try again."),
-        }
-    }
-}
-
-pub fn get_input(prompt: &str) -> String {
-    println!("{}",prompt);
-    let mut input = String::new();
-    match io::stdin().read_line(&mut input) {
-        Ok(_goes_into_input_above) => {},
-        Err(_no_updates_is_fine) => {},
-    }
-    input.trim().to_string()
-}
\ No newline at end of file
+use std::io::{self, BufRead, BufReader, Write};
+use std::sync::LazyLock;
+use regex::Regex;
+use itertools::Itertools;
+
+// Matches 'r' followed by spaces, then numbers with spaces preserved
+static REROLL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^r\s+([\d\s]+)$").unwrap());
+
+pub enum Command {
+    Reroll(Vec<usize>),
+    Pick,
+    Quit,
+    Help,
+    Reset,
+    ShowScores,
+    ShowBoard,
+    Hint,
+    Odds
+}
+
+// Where a game reads its commands from and prints to. That's the terminal for
+// a local game, or a socket for the text server.
+pub struct Console {
+    input: Box<dyn BufRead + Send>,
+    output: Box<dyn Write + Send>,
+}
+
+impl Console {
+    pub fn new(input: impl BufRead + Send + 'static, output: impl Write + Send + 'static) -> Console {
+        Console { input: Box::new(input), output: Box::new(output) }
+    }
+
+    pub fn stdio() -> Console {
+        Console::new(BufReader::new(io::stdin()), io::stdout())
+    }
+
+    // Write errors mean the other side is gone, which shows up as EOF on the next read
+    pub fn print(&mut self, text: impl AsRef<str>) {
+        let _ = writeln!(self.output, "{}", text.as_ref());
+        let _ = self.output.flush();
+    }
+
+    // Quits when the input is closed
+    pub fn get_command(&mut self) -> Command {
+        loop {
+            let Some(input) = self.get_input("") else {
+                return Command::Quit;
+            };
+            if let Some(command) = parse_command(&input) {
+                return command;
+            }
+
+            self.print("Invalid command. Try again.");
+        }
+    }
+
+    // A 0 based index, or None when the input is closed
+    pub fn get_pick(&mut self) -> Option<usize> {
+        loop {
+            let input = self.get_input("")?;
+            match input.parse::<usize>() {
+                Ok(n) if n > 0 => return Some(n - 1),
+                _ => self.print("Invalid input. Try again."),
+            }
+        }
+    }
+
+    pub fn get_input(&mut self, prompt: &str) -> Option<String> {
+        self.print(prompt);
+        let mut input = String::new();
+        match self.input.read_line(&mut input) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(input.trim().to_string()),
+        }
+    }
+}
+
+pub fn parse_command(input: &str) -> Option<Command> {
+    if let Some(caps) = REROLL.captures(input) {
+        let indices = caps.get(1).unwrap().as_str();
+        let numbers = indices.split_whitespace()
+        .map(|s| s.parse::<usize>())
+        .filter_map(Result::ok)
+        .filter(|&n| n > 0)
+        .map(|n| n - 1)
+        .unique()
+        .collect();
+        return Some(Command::Reroll(numbers));
+    }
+
+    match input {
+        "p" | "pick" => Some(Command::Pick),
+        "q" | "quit" => Some(Command::Quit),
+        "h" | "help" => Some(Command::Help),
+        "s" | "scores" => Some(Command::ShowScores),
+        "b" | "board" => Some(Command::ShowBoard),
+        "reset" => Some(Command::Reset),
+        "?" | "hint" => Some(Command::Hint),
+        "o" | "odds" => Some(Command::Odds),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert!(matches!(parse_command("?"), Some(Command::Hint)));
+        assert!(matches!(parse_command("hint"), Some(Command::Hint)));
+        assert!(matches!(parse_command("r 1 3 3"), Some(Command::Reroll(indices)) if indices == vec![0, 2]));
+        assert!(parse_command("??").is_none());
+    }
+}
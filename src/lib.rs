@@ -0,0 +1,17 @@
+pub mod arena;
+pub mod bot;
+pub mod dice;
+pub mod dice_result;
+pub mod game;
+pub mod input;
+pub mod net;
+pub mod odds;
+pub mod outcomes;
+pub mod profiles;
+pub mod review;
+pub mod ruleset;
+pub mod scores;
+pub mod simulate;
+pub mod solver;
+pub mod strategy;
+pub mod tournament;
@@ -1,9 +1,318 @@
-mod dice;
-mod dice_result;
-mod input;
-mod game;
-mod scores;
-
-fn main() {
-    game::Game::new().start();
-}
\ No newline at end of file
+use std::collections::HashMap;
+use std::env;
+use std::process::exit;
+use std::sync::Arc;
+
+use yatsy::arena;
+use yatsy::bot;
+use yatsy::game;
+use yatsy::net;
+use yatsy::profiles;
+use yatsy::review;
+use yatsy::ruleset;
+use yatsy::simulate;
+use yatsy::solver;
+use yatsy::strategy::Strategy;
+use yatsy::tournament;
+
+struct Options {
+    rules: String,
+    rules_file: Option<String>,
+    forced: bool,
+    players: Vec<String>,
+    bots: Vec<bot::Difficulty>,
+    port: u16,
+    seats: usize,
+    name: Option<String>,
+    // Arguments that aren't flags, like the address to connect to
+    arguments: Vec<String>,
+    profiles: String,
+    seed: Option<u64>,
+    duplicate: bool,
+    review: bool,
+    format: tournament::Format,
+    best_of: u32,
+    games: usize,
+    strategy: bot::Difficulty,
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mode = match args.first().map(String::as_str) {
+        Some("server") | Some("telnet") | Some("connect") | Some("tournament") | Some("profiles") | Some("solve") | Some("simulate") | Some("arena") => args.remove(0),
+        _ => "local".to_string(),
+    };
+    let options = parse_options(&args);
+    let allowed = match mode.as_str() {
+        "connect" => 1,
+        "profiles" => 2,
+        _ => 0,
+    };
+    if let Some(argument) = options.arguments.get(allowed) {
+        fail(&format!("Unknown argument: {}", argument));
+    }
+
+    match mode.as_str() {
+        "server" => {
+            let server = net::Server::bind(&format!("0.0.0.0:{}", options.port), get_ruleset(&options), options.forced, options.seats)
+                .unwrap_or_else(|e| fail(&e));
+            println!("Waiting for {} players on port {}", options.seats, server.local_addr().port());
+            if let Err(e) = server.run() {
+                fail(&e);
+            }
+        },
+        "telnet" => {
+            let server = net::TextServer::bind(&format!("0.0.0.0:{}", options.port), get_ruleset(&options), options.forced)
+                .unwrap_or_else(|e| fail(&e));
+            println!("Serving games on port {}, play with: nc localhost {}", server.local_addr().port(), server.local_addr().port());
+            if let Err(e) = server.run() {
+                fail(&e);
+            }
+        },
+        "connect" => {
+            let Some(address) = options.arguments.first() else {
+                fail("Give the address of the server, like localhost:4000");
+            };
+            let Some(name) = options.name else {
+                fail("Give your name with --name");
+            };
+            if let Err(e) = net::connect(address, &name) {
+                fail(&e);
+            }
+        },
+        "solve" => {
+            let mut solver = solver::Solver::cached(get_ruleset(&options), options.forced).unwrap_or_else(|e| fail(&e));
+            println!("Solving, this can take a few minutes...");
+            let expected = solver.solve();
+            let path = solver.cache_path();
+            if let Err(e) = solver.save(&path) {
+                fail(&e);
+            }
+            println!("Expected score with the best strategy: {:.2}p", expected);
+            println!("Saved the strategy to {}", path.display());
+        },
+        "simulate" => {
+            let seed = options.seed.unwrap_or_else(rand::random);
+            if options.strategy == bot::Difficulty::Solver {
+                println!("Working out the best strategy, this can take a minute the first time...");
+            }
+            let ruleset = get_ruleset(&options);
+            let mut strategy = bot::Bot::new(options.strategy, ruleset.clone(), options.forced).unwrap_or_else(|e| fail(&e)).with_seed(seed);
+            let summary = simulate::simulate(ruleset, options.forced, &mut strategy, options.games, seed)
+                .unwrap_or_else(|e| fail(&e));
+            println!("Seed: {}", seed);
+            println!("{}", summary.format());
+        },
+        "arena" => {
+            let seed = options.seed.unwrap_or_else(rand::random);
+            let ruleset = get_ruleset(&options);
+            if options.bots.contains(&bot::Difficulty::Solver) {
+                println!("Working out the best strategy, this can take a minute the first time...");
+            }
+            let mut strategies = options.bots.iter().enumerate().map(|(i, &difficulty)| {
+                let bot = bot::Bot::new(difficulty, ruleset.clone(), options.forced).unwrap_or_else(|e| fail(&e));
+                Box::new(bot.with_seed(seed.wrapping_add(i as u64))) as Box<dyn Strategy>
+            }).collect::<Vec<_>>();
+            let entries = arena::run(ruleset, options.forced, &mut strategies, options.games, seed)
+                .unwrap_or_else(|e| fail(&e));
+            println!("Seed: {}", seed);
+            println!("--- {} games on the same dice ---", options.games);
+            println!("{}", arena::format_results(&entries));
+        },
+        "profiles" => {
+            let mut profiles = load_profiles(&options);
+            match options.arguments.as_slice() {
+                [] => println!("{}", profiles.format_ranking()),
+                [add, name] if add == "add" => {
+                    profiles.add(name).unwrap_or_else(|e| fail(&e));
+                    save_profiles(&profiles);
+                    println!("Added a profile for {}", name.trim());
+                },
+                [name] => match profiles.format_profile(name) {
+                    Some(profile) => println!("{}", profile),
+                    None => fail(&format!("There is no profile for {}", name)),
+                },
+                _ => fail("Use: yatsy profiles [<name> | add <name>]"),
+            }
+        },
+        "tournament" => {
+            let ruleset = get_ruleset(&options);
+            let mut profiles = load_profiles(&options);
+            if options.players.len() + options.bots.len() < 2 {
+                fail("Give the players with --players and --bots");
+            }
+            let mut players = profiles.choose(&options.players).unwrap_or_else(|e| fail(&e));
+
+            // Bots enter under their own names and are seated whenever they play
+            let mut bots = HashMap::<String, Box<dyn Strategy + Send>>::new();
+            for &difficulty in &options.bots {
+                if difficulty == bot::Difficulty::Solver {
+                    println!("Working out the best strategy for the bot, this can take a minute the first time...");
+                }
+                let bot = bot::Bot::new(difficulty, ruleset.clone(), options.forced).unwrap_or_else(|e| fail(&e));
+                let name = game::unique_name(&bot.name(), &players);
+                players.push(name.clone());
+                bots.insert(name, Box::new(bot));
+            }
+
+            let mut tournament = tournament::Tournament::new(options.format, options.best_of, players)
+                .unwrap_or_else(|e| fail(&e));
+            let result = tournament.run(|players| {
+                let mut game = game::Game::new(ruleset.clone(), options.forced, players);
+                for player in game.players.iter_mut() {
+                    player.bot = bots.remove(&player.name);
+                }
+                game.start();
+                for player in game.players.iter_mut() {
+                    if let Some(bot) = player.bot.take() {
+                        bots.insert(player.name.clone(), bot);
+                    }
+                }
+                if game.is_over() {
+                    profiles.record_game(&game);
+                    save_profiles(&profiles);
+                }
+                game
+            });
+            if let Err(e) = result {
+                fail(&e);
+            }
+        },
+        _ => {
+            // Multiplayer games are rated, so they're played by profiles
+            let mut profiles = None;
+            let mut players = options.players.clone();
+            if players.is_empty() {
+                players.push("Player".to_string());
+            }
+            if players.len() > 1 {
+                let loaded = load_profiles(&options);
+                players = loaded.choose(&players).unwrap_or_else(|e| fail(&e));
+                profiles = Some(loaded);
+            }
+
+            let ruleset = get_ruleset(&options);
+            // Checked before playing, so a game isn't played for a review that can't be done
+            let mut solver = options.review.then(|| solver::Solver::cached(ruleset.clone(), options.forced).unwrap_or_else(|e| fail(&e)));
+            let mut game = game::Game::new(ruleset.clone(), options.forced, players);
+            if game.players.len() + options.bots.len() > 8 {
+                fail("A game has at most 8 players, bots included");
+            }
+            for &difficulty in &options.bots {
+                if difficulty == bot::Difficulty::Solver {
+                    println!("Working out the best strategy for the bot, this can take a minute the first time...");
+                }
+                game.add_bot(Box::new(bot::Bot::new(difficulty, ruleset.clone(), options.forced).unwrap_or_else(|e| fail(&e))));
+            }
+            if options.seed.is_some() || options.duplicate {
+                game = game.with_seed(options.seed.unwrap_or_else(rand::random), options.duplicate);
+            }
+            game.start();
+
+            if let Some(solver) = solver.as_mut() && game.is_over() {
+                println!("\nReviewing the game, this can take a minute the first time...");
+                for review in review::review(&game, solver) {
+                    println!("\n{}", review.format());
+                }
+                // The cache only saves time
+                let _ = solver.save(&solver.cache_path());
+            }
+
+            if let Some(mut profiles) = profiles && game.is_over() {
+                profiles.record_game(&game);
+                save_profiles(&profiles);
+                println!("\n--- Ratings ---\n{}", profiles.format_ranking());
+            }
+        },
+    }
+}
+
+fn parse_options(args: &[String]) -> Options {
+    let mut options = Options {
+        rules: "scandinavian".to_string(),
+        rules_file: None,
+        forced: false,
+        players: Vec::new(),
+        bots: Vec::new(),
+        port: 4000,
+        seats: 2,
+        name: None,
+        arguments: Vec::new(),
+        profiles: "profiles.json".to_string(),
+        seed: None,
+        duplicate: false,
+        review: false,
+        format: tournament::Format::RoundRobin,
+        best_of: 1,
+        games: 1000,
+        strategy: bot::Difficulty::Greedy,
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--rules" => options.rules = iter.next().cloned().unwrap_or_default(),
+            "--rules-file" => options.rules_file = iter.next().cloned(),
+            "--forced" => options.forced = true,
+            "--players" => options.players = game::parse_players(iter.next().map_or("", |n| n.as_str())).unwrap_or_else(|e| fail(&e)),
+            "--bots" => options.bots = iter.next().map_or("", |b| b.as_str()).split(',').map(|name| {
+                bot::Difficulty::parse(name.trim()).unwrap_or_else(|| fail(&format!("Unknown bot: {}", name)))
+            }).collect(),
+            "--port" => options.port = parse_number(iter.next(), "--port"),
+            "--seats" => options.seats = parse_number(iter.next(), "--seats"),
+            "--name" => options.name = iter.next().cloned(),
+            "--seed" => options.seed = Some(parse_number(iter.next(), "--seed")),
+            "--duplicate" => options.duplicate = true,
+            "--review" => options.review = true,
+            "--format" => {
+                let name = iter.next().map_or("", |f| f.as_str());
+                options.format = tournament::Format::parse(name).unwrap_or_else(|| fail(&format!("Unknown format: {}", name)));
+            },
+            "--best-of" => options.best_of = parse_number(iter.next(), "--best-of"),
+            "--games" => options.games = parse_number(iter.next(), "--games"),
+            "--strategy" => {
+                let name = iter.next().map_or("", |s| s.as_str());
+                options.strategy = bot::Difficulty::parse(name).unwrap_or_else(|| fail(&format!("Unknown strategy: {}", name)));
+            },
+            "--profiles" => options.profiles = iter.next().cloned().unwrap_or_default(),
+            _ if !arg.starts_with("--") => options.arguments.push(arg.clone()),
+            _ => fail(&format!("Unknown argument: {}", arg)),
+        }
+    }
+    options
+}
+
+fn get_ruleset(options: &Options) -> Arc<dyn ruleset::Ruleset> {
+    match &options.rules_file {
+        Some(path) => match ruleset::Custom::load(path) {
+            Ok(custom) => Arc::new(custom),
+            Err(e) => fail(&e),
+        },
+        None => match ruleset::get_ruleset(&options.rules) {
+            Some(ruleset) => ruleset,
+            None => fail(&format!("Unknown rules: {}", options.rules)),
+        }
+    }
+}
+
+fn load_profiles(options: &Options) -> profiles::Profiles {
+    profiles::Profiles::load(&options.profiles).unwrap_or_else(|e| fail(&e))
+}
+
+fn save_profiles(profiles: &profiles::Profiles) {
+    if let Err(e) = profiles.save() {
+        fail(&e);
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: Option<&String>, flag: &str) -> T {
+    match value.map(|v| v.parse::<T>()) {
+        Some(Ok(n)) => n,
+        _ => fail(&format!("{} needs a number", flag)),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    println!("{}", message);
+    exit(1);
+}
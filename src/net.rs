@@ -0,0 +1,9 @@
+mod client;
+mod protocol;
+mod server;
+mod text;
+
+pub use client::connect;
+pub use protocol::{Choice, ClientMessage, GameState, ServerMessage};
+pub use server::Server;
+pub use text::TextServer;
@@ -0,0 +1,196 @@
+use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
+use std::process::exit;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use super::protocol::{self, Choice, ClientMessage, GameState, ServerMessage};
+use crate::game::Pick;
+use crate::input;
+use crate::scores;
+
+// Plays on a server from the terminal, with the same commands as a local game
+pub fn connect(address: &str, name: &str) -> Result<(), String> {
+    let mut stream = TcpStream::connect(address).map_err(|e| format!("Could not connect to {}: {}", address, e))?;
+    let reader = stream.try_clone().map_err(|e| e.to_string())?;
+    protocol::send(&mut stream, &ClientMessage::Join { name: name.to_string() }).map_err(|e| e.to_string())?;
+
+    let state = Arc::new(Mutex::new(None::<GameState>));
+    let shared = state.clone();
+    thread::spawn(move || receive(reader, shared));
+
+    let mut console = input::Console::stdio();
+    print_help();
+    loop {
+        let message = match console.get_command() {
+            input::Command::Reroll(dice) => ClientMessage::Reroll { dice },
+            input::Command::Pick => {
+                let state = state.lock().unwrap().clone();
+                match state {
+                    Some(state) if state.player == name => match pick(&mut console, &state) {
+                        Some((pick, column)) => ClientMessage::Place { pick, column },
+                        None => exit(0),
+                    },
+                    _ => {
+                        println!("It's not your turn!");
+                        continue;
+                    }
+                }
+            },
+            input::Command::ShowScores | input::Command::ShowBoard => {
+                match state.lock().unwrap().as_ref() {
+                    Some(state) => println!("{}", scores::format_board(&state.players, &state.board)),
+                    None => println!("The game hasn't started yet."),
+                }
+                continue;
+            },
+            input::Command::Help => {
+                print_help();
+                continue;
+            },
+            input::Command::Reset => {
+                println!("A network game can't be reset.");
+                continue;
+            },
+            input::Command::Hint => {
+                println!("Hints aren't available in network games.");
+                continue;
+            },
+            input::Command::Odds => {
+                println!("Odds aren't available in network games.");
+                continue;
+            },
+            input::Command::Quit => exit(0),
+        };
+
+        protocol::send(&mut stream, &message).map_err(|e| format!("Lost the connection: {}", e))?;
+    }
+}
+
+fn receive(stream: TcpStream, state: Arc<Mutex<Option<GameState>>>) {
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let message = match protocol::parse::<ServerMessage>(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                println!("Invalid message from the server: {}", e);
+                continue;
+            }
+        };
+
+        match message {
+            ServerMessage::Welcome { name, rules } => println!("Joined a game of {} as {}", rules, name),
+            ServerMessage::Lobby { players, seats } => {
+                println!("Waiting for players ({}/{}): {}", players.len(), seats, players.join(", "));
+            },
+            ServerMessage::State(new_state) => {
+                let mut state = state.lock().unwrap();
+                if state.as_ref().is_none_or(|s| s.player != new_state.player) {
+                    println!("\n--- {}'s turn ---", new_state.player);
+                }
+                println!("\nDice: {}, Rerolls left: {}", new_state.dice, new_state.rerolls);
+                if !new_state.disconnected.is_empty() {
+                    println!("Waiting for {} to reconnect", new_state.disconnected.join(", "));
+                }
+                *state = Some(new_state);
+            },
+            ServerMessage::Error { message } => println!("{}", message),
+            ServerMessage::GameOver { winner, ranking } => {
+                if let Some(state) = state.lock().unwrap().as_ref() {
+                    println!("{}", scores::format_board(&state.players, &state.board));
+                }
+                if let Some(winner) = winner {
+                    println!("\n{} wins the game!", winner);
+                }
+                println!("\n--- Final ranking ---");
+                for (rank, name, total) in ranking {
+                    println!("{}. {}\t{}p", rank, name, total);
+                }
+                exit(0);
+            },
+        }
+    }
+
+    println!("Lost the connection to the server.");
+    exit(1);
+}
+
+fn print_help() {
+    println!("Enter \"r\" followed by indices to reroll dice. Indices are 1 based and separated by spaces.");
+    println!("Enter \"p\" to pick a result from the available options.");
+    println!("Enter \"s\" or \"b\" to show the scoreboard.");
+    println!("Enter \"q\" to quit, you can rejoin later with the same name.");
+    println!("Enter \"h\" to show this help message.")
+}
+
+// Same menus as Game::handle_pick, built from the server's options.
+// None when the input is closed.
+fn pick(console: &mut input::Console, state: &GameState) -> Option<(Pick, usize)> {
+    println!("Pick a result:");
+    for (i, result) in state.results.iter().enumerate() {
+        println!("{}: {}p\t{}", i + 1, result.score, result.name);
+    }
+    println!("{}: Score 0 in a row", state.results.len() + 1);
+    println!("{}: Strike row", state.results.len() + 2);
+
+    let index = loop {
+        let index = console.get_pick()?;
+        if index <= state.results.len() + 1 {
+            break index;
+        }
+        println!("Invalid selection. Try again.");
+    };
+
+    if index == state.results.len() {
+        let choice = pick_choice(console, "Score 0 in a row:", &state.open)?;
+        return Some((Pick::Zero(choice.result_type), pick_column(console, choice, false)?));
+    }
+    if index == state.results.len() + 1 {
+        let choice = pick_choice(console, "Strike a result:", &state.open)?;
+        return Some((Pick::Strike(choice.result_type), pick_column(console, choice, false)?));
+    }
+
+    let choice = &state.results[index];
+    Some((Pick::Result(choice.result_type), pick_column(console, choice, true)?))
+}
+
+fn pick_choice<'a>(console: &mut input::Console, prompt: &str, choices: &'a [Choice]) -> Option<&'a Choice> {
+    println!("{}", prompt);
+    for (i, choice) in choices.iter().enumerate() {
+        println!("{}: {}", i + 1, choice.name);
+    }
+
+    loop {
+        let index = console.get_pick()?;
+        if index < choices.len() {
+            return Some(&choices[index]);
+        }
+        println!("Invalid selection. Try again.");
+    }
+}
+
+fn pick_column(console: &mut input::Console, choice: &Choice, show_score: bool) -> Option<usize> {
+    let columns = &choice.columns;
+    if columns.iter().all(|&(_, m)| m == columns[0].1) {
+        return Some(columns[0].0);
+    }
+
+    println!("Pick a column:");
+    for (i, (_, multiplier)) in columns.iter().enumerate() {
+        if show_score {
+            println!("{}: x{} ({}p)", i + 1, multiplier, choice.score * multiplier);
+        } else {
+            println!("{}: x{}", i + 1, multiplier);
+        }
+    }
+
+    loop {
+        let index = console.get_pick()?;
+        if index < columns.len() {
+            return Some(columns[index].0);
+        }
+        println!("Invalid selection. Try again.");
+    }
+}
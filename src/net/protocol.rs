@@ -0,0 +1,94 @@
+use std::io::{self, Write};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::dice;
+use crate::dice_result::ResultType;
+use crate::game::{Game, Pick};
+
+// Messages are sent as one JSON object per line, tagged with a "type" field,
+// e.g. {"type":"reroll","dice":[0,3]}. Dice indices are 0 based.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join { name: String },
+    Reroll { dice: Vec<usize> },
+    Place { pick: Pick, column: usize },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Welcome { name: String, rules: String },
+    Lobby { players: Vec<String>, seats: usize },
+    State(GameState),
+    Error { message: String },
+    GameOver { winner: Option<String>, ranking: Vec<(usize, String, i32)> },
+}
+
+// Everything a client needs to show the game and offer the current player's options
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GameState {
+    pub player: String,
+    pub dice: dice::DiceRoll,
+    pub rerolls: i32,
+    // Rows the dice score in
+    pub results: Vec<Choice>,
+    // Rows that can be scored as zero or struck
+    pub open: Vec<Choice>,
+    pub players: Vec<String>,
+    pub board: Vec<(String, Vec<String>)>,
+    pub disconnected: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Choice {
+    pub result_type: ResultType,
+    pub name: String,
+    pub score: i32,
+    // Open columns with their multipliers
+    pub columns: Vec<(usize, i32)>,
+}
+
+impl GameState {
+    pub fn new(game: &Game, disconnected: Vec<String>) -> GameState {
+        let choice = |result_type: ResultType, score: i32| Choice {
+            result_type,
+            name: game.ruleset.category_name(result_type),
+            score,
+            columns: game.score_card().get_open_columns(result_type).into_iter()
+                .map(|c| (c, game.ruleset.column_multiplier(c)))
+                .collect(),
+        };
+
+        GameState {
+            player: game.current_player().name.clone(),
+            dice: game.dice.clone(),
+            rerolls: game.rerolls,
+            results: game.available_results().iter().map(|r| choice(r.result_type, r.score)).collect(),
+            open: game.open_types().into_iter().map(|t| choice(t, 0)).collect(),
+            players: game.players.iter().map(|p| p.name.clone()).collect(),
+            board: game.scoreboard(),
+            disconnected,
+        }
+    }
+}
+
+// Rank, name and total of each player
+pub fn ranking(game: &Game) -> Vec<(usize, String, i32)> {
+    game.ranking().into_iter()
+        .map(|(rank, p)| (rank, p.name.clone(), p.score_card.get_total()))
+        .collect()
+}
+
+pub fn send<T: Serialize>(stream: &mut impl Write, message: &T) -> io::Result<()> {
+    let mut line = serde_json::to_string(message).map_err(io::Error::other)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.flush()
+}
+
+pub fn parse<T: DeserializeOwned>(line: &str) -> Result<T, String> {
+    serde_json::from_str(line).map_err(|e| e.to_string())
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use super::protocol::{self, ClientMessage, GameState, ServerMessage};
+use crate::game::Game;
+use crate::ruleset::Ruleset;
+
+// Hosts a single game. The server owns the dice and the score cards, clients
+// only send what they want to do and get the resulting state back.
+pub struct Server {
+    listener: TcpListener,
+    ruleset: Arc<dyn Ruleset>,
+    forced: bool,
+    seats: usize,
+}
+
+enum Event {
+    Connected(usize, TcpStream),
+    Message(usize, ClientMessage),
+    Invalid(usize, String),
+    Disconnected(usize),
+}
+
+struct Seat {
+    name: String,
+    // None while the player is disconnected
+    connection: Option<usize>,
+}
+
+struct Session {
+    ruleset: Arc<dyn Ruleset>,
+    forced: bool,
+    needed: usize,
+    connections: HashMap<usize, TcpStream>,
+    seats: Vec<Seat>,
+    game: Option<Game>,
+}
+
+impl Server {
+    pub fn bind(address: &str, ruleset: Arc<dyn Ruleset>, forced: bool, seats: usize) -> Result<Server, String> {
+        if !(1..=8).contains(&seats) {
+            return Err("A game needs between 1 and 8 players".to_string());
+        }
+        let listener = TcpListener::bind(address).map_err(|e| format!("Could not listen on {}: {}", address, e))?;
+        Ok(Server { listener, ruleset, forced, seats })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.listener.local_addr().unwrap()
+    }
+
+    // Plays one game and returns the final ranking
+    pub fn run(self) -> Result<Vec<(usize, String, i32)>, String> {
+        let (tx, rx) = mpsc::channel();
+        let listener = self.listener.try_clone().map_err(|e| e.to_string())?;
+        thread::spawn(move || accept(listener, tx));
+
+        let mut session = Session {
+            ruleset: self.ruleset,
+            forced: self.forced,
+            needed: self.seats,
+            connections: HashMap::new(),
+            seats: Vec::new(),
+            game: None,
+        };
+
+        for event in rx {
+            match event {
+                Event::Connected(id, stream) => {
+                    session.connections.insert(id, stream);
+                },
+                Event::Message(id, message) => {
+                    if let Some(ranking) = session.handle(id, message) {
+                        return Ok(ranking);
+                    }
+                },
+                Event::Invalid(id, e) => session.send(id, &ServerMessage::Error { message: format!("Invalid message: {}", e) }),
+                Event::Disconnected(id) => session.disconnect(id),
+            }
+        }
+        Err("The server stopped accepting connections".to_string())
+    }
+}
+
+fn accept(listener: TcpListener, tx: Sender<Event>) {
+    for (id, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let Ok(writer) = stream.try_clone() else {
+            continue;
+        };
+        if tx.send(Event::Connected(id, writer)).is_err() {
+            return;
+        }
+
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let event = match protocol::parse::<ClientMessage>(&line) {
+                    Ok(message) => Event::Message(id, message),
+                    Err(e) => Event::Invalid(id, e),
+                };
+                if tx.send(event).is_err() {
+                    return;
+                }
+            }
+            let _ = tx.send(Event::Disconnected(id));
+        });
+    }
+}
+
+impl Session {
+    // Returns the final ranking once the game is over
+    fn handle(&mut self, id: usize, message: ClientMessage) -> Option<Vec<(usize, String, i32)>> {
+        let seat = self.seats.iter().position(|s| s.connection == Some(id));
+        let result = match (message, seat) {
+            (ClientMessage::Join { name }, None) => self.join(id, name.trim()),
+            (ClientMessage::Join { .. }, Some(_)) => Err("You have already joined!".to_string()),
+            (_, None) => Err("Join the game first!".to_string()),
+            (message, Some(seat)) => self.play(seat, message),
+        };
+
+        if let Err(message) = result {
+            self.send(id, &ServerMessage::Error { message });
+            return None;
+        }
+
+        let game = self.game.as_ref()?;
+        if !game.is_over() {
+            return None;
+        }
+        let winner = game.winner.map(|i| game.players[i].name.clone());
+        let ranking = protocol::ranking(game);
+        self.broadcast(&ServerMessage::GameOver { winner, ranking: ranking.clone() });
+        Some(ranking)
+    }
+
+    fn join(&mut self, id: usize, name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("Pick a name!".to_string());
+        }
+
+        match self.seats.iter().position(|s| s.name == name) {
+            Some(seat) if self.seats[seat].connection.is_some() => return Err(format!("{} is already playing!", name)),
+            Some(seat) => self.seats[seat].connection = Some(id),
+            None if self.game.is_some() || self.seats.len() == self.needed => return Err("The game is full!".to_string()),
+            None => self.seats.push(Seat { name: name.to_string(), connection: Some(id) }),
+        }
+
+        self.send(id, &ServerMessage::Welcome { name: name.to_string(), rules: self.ruleset.name().to_string() });
+        if self.game.is_none() && self.seats.len() == self.needed {
+            let names = self.seats.iter().map(|s| s.name.clone()).collect();
+            self.game = Some(Game::new(self.ruleset.clone(), self.forced, names));
+        }
+        self.broadcast_state();
+        Ok(())
+    }
+
+    fn play(&mut self, seat: usize, message: ClientMessage) -> Result<(), String> {
+        let Some(game) = self.game.as_mut() else {
+            return Err("Waiting for more players!".to_string());
+        };
+        if game.current != seat {
+            return Err("It's not your turn!".to_string());
+        }
+
+        match message {
+            ClientMessage::Reroll { dice } => game.reroll(dice)?,
+            ClientMessage::Place { pick, column } => game.place(pick, column)?,
+            ClientMessage::Join { .. } => unreachable!(),
+        }
+        self.broadcast_state();
+        Ok(())
+    }
+
+    fn disconnect(&mut self, id: usize) {
+        self.connections.remove(&id);
+        let Some(seat) = self.seats.iter().position(|s| s.connection == Some(id)) else {
+            return;
+        };
+
+        // Seats are only held for players of a running game
+        if self.game.is_some() {
+            self.seats[seat].connection = None;
+        } else {
+            self.seats.remove(seat);
+        }
+        self.broadcast_state();
+    }
+
+    fn broadcast_state(&mut self) {
+        let message = match &self.game {
+            Some(game) => {
+                let disconnected = self.seats.iter().filter(|s| s.connection.is_none()).map(|s| s.name.clone()).collect();
+                ServerMessage::State(GameState::new(game, disconnected))
+            },
+            None => ServerMessage::Lobby {
+                players: self.seats.iter().map(|s| s.name.clone()).collect(),
+                seats: self.needed,
+            },
+        };
+        self.broadcast(&message);
+    }
+
+    // Only players that have joined get game messages
+    fn broadcast(&mut self, message: &ServerMessage) {
+        let ids = self.seats.iter().filter_map(|s| s.connection).collect::<Vec<_>>();
+        for id in ids {
+            self.send(id, message);
+        }
+    }
+
+    // A failed write shows up as a disconnect from the reader thread
+    fn send(&mut self, id: usize, message: &ServerMessage) {
+        if let Some(stream) = self.connections.get_mut(&id) {
+            let _ = protocol::send(stream, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Pick;
+    use crate::ruleset::Scandinavian;
+    use std::io::Lines;
+
+    struct TestClient {
+        stream: TcpStream,
+        lines: Lines<BufReader<TcpStream>>,
+    }
+
+    impl TestClient {
+        fn join(address: SocketAddr, name: &str) -> TestClient {
+            let stream = TcpStream::connect(address).unwrap();
+            let lines = BufReader::new(stream.try_clone().unwrap()).lines();
+            let mut client = TestClient { stream, lines };
+            client.send(ClientMessage::Join { name: name.to_string() });
+            client
+        }
+
+        fn send(&mut self, message: ClientMessage) {
+            protocol::send(&mut self.stream, &message).unwrap();
+        }
+
+        fn receive(&mut self) -> ServerMessage {
+            protocol::parse(&self.lines.next().unwrap().unwrap()).unwrap()
+        }
+
+        // Skips ahead to the next game state
+        fn state(&mut self) -> GameState {
+            loop {
+                if let ServerMessage::State(state) = self.receive() {
+                    return state;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_game_over_tcp() {
+        let server = Server::bind("127.0.0.1:0", Arc::new(Scandinavian), false, 2).unwrap();
+        let address = server.local_addr();
+        let handle = thread::spawn(move || server.run());
+
+        let mut kim = TestClient::join(address, "Kim");
+        assert!(matches!(kim.receive(), ServerMessage::Welcome { .. }));
+        assert_eq!(kim.receive(), ServerMessage::Lobby { players: vec!["Kim".to_string()], seats: 2 });
+
+        let mut anna = TestClient::join(address, "Anna");
+        assert_eq!(kim.state().player, "Kim");
+        assert_eq!(anna.state().players, vec!["Kim", "Anna"]);
+
+        anna.send(ClientMessage::Reroll { dice: vec![0] });
+        assert_eq!(anna.receive(), ServerMessage::Error { message: "It's not your turn!".to_string() });
+
+        kim.send(ClientMessage::Reroll { dice: vec![7] });
+        assert!(matches!(kim.receive(), ServerMessage::Error { .. }));
+
+        // Anna drops out and comes back under the same name
+        drop(anna);
+        assert_eq!(kim.state().disconnected, vec!["Anna"]);
+        let mut anna = TestClient::join(address, "Anna");
+        assert!(matches!(anna.receive(), ServerMessage::Welcome { .. }));
+        assert!(anna.state().disconnected.is_empty());
+        assert!(kim.state().disconnected.is_empty());
+
+        let mut clients = [kim, anna];
+        for result_type in Scandinavian.categories() {
+            for player in 0..clients.len() {
+                clients[player].send(ClientMessage::Place { pick: Pick::Strike(result_type), column: 0 });
+                clients.iter_mut().for_each(|c| assert!(matches!(c.receive(), ServerMessage::State(_))));
+            }
+        }
+
+        for client in clients.iter_mut() {
+            match client.receive() {
+                ServerMessage::GameOver { ranking, .. } => assert_eq!(ranking.len(), 2),
+                message => panic!("Expected game over, got {:?}", message),
+            }
+        }
+        assert_eq!(handle.join().unwrap().unwrap()[0], (1, "Kim".to_string(), 0));
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::BufReader;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use crate::game::{self, Game};
+use crate::input::Console;
+use crate::ruleset::Ruleset;
+
+// Serves the terminal game as plain text, so it can be played with telnet or
+// netcat. Every connection gets its own game.
+pub struct TextServer {
+    listener: TcpListener,
+    ruleset: Arc<dyn Ruleset>,
+    forced: bool,
+}
+
+impl TextServer {
+    pub fn bind(address: &str, ruleset: Arc<dyn Ruleset>, forced: bool) -> Result<TextServer, String> {
+        let listener = TcpListener::bind(address).map_err(|e| format!("Could not listen on {}: {}", address, e))?;
+        Ok(TextServer { listener, ruleset, forced })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.listener.local_addr().unwrap()
+    }
+
+    pub fn run(self) -> Result<(), String> {
+        for stream in self.listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let ruleset = self.ruleset.clone();
+            let forced = self.forced;
+            thread::spawn(move || session(stream, ruleset, forced));
+        }
+        Ok(())
+    }
+}
+
+fn session(stream: TcpStream, ruleset: Arc<dyn Ruleset>, forced: bool) {
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let mut console = Console::new(BufReader::new(reader), stream);
+
+    // Several names make a shared game, taking turns on the same connection
+    let names = loop {
+        let Some(input) = console.get_input("Enter your name, or several separated by commas to share the game:") else {
+            return;
+        };
+        match game::parse_players(&input) {
+            Ok(names) => break names,
+            Err(e) => console.print(e),
+        }
+    };
+
+    Game::with_console(ruleset, forced, names, console).start();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ruleset::Scandinavian;
+    use std::io::{Read, Write};
+
+    fn play(address: SocketAddr, commands: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(commands.as_bytes()).unwrap();
+        stream.shutdown(std::net::Shutdown::Write).unwrap();
+        let mut output = String::new();
+        stream.read_to_string(&mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_sessions() {
+        let server = TextServer::bind("127.0.0.1:0", Arc::new(Scandinavian), false).unwrap();
+        let address = server.local_addr();
+        thread::spawn(move || server.run());
+
+        let output = play(address, "Kim\nr 1 2\nr 9\ns\nq\n");
+        assert!(output.contains("Playing Scandinavian Yatzy"));
+        assert!(output.contains("Rerolls left: 1"));
+        assert!(output.contains("Dice are numbered from 1 to 5!"));
+        assert!(output.contains("--- Score Card ---"));
+
+        // Closing the connection mid game ends the session
+        let output = play(address, ",\nKim,Anna\np\n");
+        assert!(output.contains("Give between 1 and 8 player names"));
+        assert!(output.contains("--- Kim's turn ---"));
+        assert!(!output.contains("Game over"));
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use super::dice;
+use super::dice_result::{self, ResultType};
+use super::outcomes::Outcomes;
+use super::ruleset::Ruleset;
+
+// The chance of filling in each category by the end of the turn, and the
+// expected score, when holding the dice with the highest expected score in
+// that category every reroll. Both come from the same holds.
+// Like the solver, it leaves out scoring that depends on the card.
+pub struct Odds {
+    categories: Vec<ResultType>,
+    outcomes: Outcomes,
+    // The score of each outcome in each category
+    scores: Vec<Vec<i32>>,
+}
+
+impl Odds {
+    pub fn new(ruleset: Arc<dyn Ruleset>) -> Result<Odds, String> {
+        let dice_count = ruleset.dice_count();
+        let faces = ruleset.faces();
+        if dice_count > 6 {
+            return Err(format!("Odds can't be worked out for {}, it has more than 6 dice", ruleset.name()));
+        }
+
+        let outcomes = Outcomes::new(dice_count, faces);
+        let categories = ruleset.categories();
+        let scores = outcomes.outcomes().iter().map(|outcome| {
+            let roll = dice::DiceRoll { dice: outcome.clone(), faces };
+            let results = dice_result::get_results(ruleset.as_ref(), &roll, 1);
+            categories.iter()
+                .map(|&t| results.iter().find(|r| r.result_type == t).map_or(0, |r| r.score))
+                .collect()
+        }).collect();
+
+        Ok(Odds { categories, outcomes, scores })
+    }
+
+    // The chance of scoring in each of the categories and the expected score,
+    // going for that category with the rerolls left
+    pub fn category_odds(&self, dice: &dice::DiceRoll, rerolls: i32, categories: &[ResultType]) -> Vec<(ResultType, f64, f64)> {
+        let outcome = self.outcomes.outcome(&dice.dice).unwrap();
+        let rerolls = rerolls.max(0) as usize;
+
+        categories.iter().filter_map(|&t| {
+            let c = self.categories.iter().position(|&category| category == t)?;
+            let mut hits = self.scores.iter().map(|s| if s[c] > 0 { 1.0 } else { 0.0 }).collect::<Vec<f64>>();
+            let mut points = self.scores.iter().map(|s| s[c] as f64).collect::<Vec<f64>>();
+            for _ in 0..rerolls {
+                let keep_points = self.outcomes.expected_values(&points);
+                let keep_hits = self.outcomes.expected_values(&hits);
+                // The chance follows the hold that's best for the score, the more likely one on a tie
+                let holds = (0..points.len()).map(|o| {
+                    *self.outcomes.outcome_keeps(o).iter()
+                        .max_by(|&&a, &&b| keep_points[a].total_cmp(&keep_points[b]).then(keep_hits[a].total_cmp(&keep_hits[b])))
+                        .unwrap()
+                }).collect::<Vec<_>>();
+                points = holds.iter().map(|&k| keep_points[k]).collect();
+                hits = holds.iter().map(|&k| keep_hits[k]).collect();
+            }
+            Some((t, hits[outcome], points[outcome]))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ruleset::Scandinavian;
+
+    fn roll(dice: &[i32]) -> dice::DiceRoll {
+        dice::DiceRoll { dice: dice.to_vec(), faces: 6 }
+    }
+
+    #[test]
+    fn test_category_odds() {
+        let odds = Odds::new(Arc::new(Scandinavian)).unwrap();
+        let dice = roll(&[6, 6, 6, 6, 2]);
+
+        let made = odds.category_odds(&dice, 0, &[ResultType::FourOfAKind, ResultType::Yatsy]);
+        assert_eq!(made, vec![(ResultType::FourOfAKind, 1.0, 24.0), (ResultType::Yatsy, 0.0, 0.0)]);
+
+        // Rerolling the 2 twice for a fifth 6
+        let (_, chance, expected) = odds.category_odds(&dice, 2, &[ResultType::Yatsy])[0];
+        let hit = 1.0 - (5.0 / 6.0f64).powi(2);
+        assert!((chance - hit).abs() < 1e-9);
+        assert!((expected - 50.0 * hit).abs() < 1e-9);
+
+        // Going for a higher pair scores more than keeping the ones, but can miss
+        let (_, chance, expected) = odds.category_odds(&roll(&[1, 1, 4, 5, 6]), 1, &[ResultType::Pair])[0];
+        assert!(chance < 1.0);
+        assert!(expected > 8.0);
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use itertools::Itertools;
+
+// Every distinct roll of a number of dice, as sorted dice, and the exact
+// chances of going from one to another by holding some dice and rerolling
+// the rest. Five six-sided dice have 252 outcomes and 462 ways to hold dice.
+//
+// Outcomes and keeps are referred to by index. The distributions are worked
+// out the first time they're asked for.
+pub struct Outcomes {
+    dice_count: usize,
+    faces: i32,
+    outcomes: Vec<Vec<i32>>,
+    outcome_index: HashMap<Vec<i32>, usize>,
+    // Every multiset of dice that can be held, from none to all of them
+    keeps: Vec<Vec<i32>>,
+    keep_index: HashMap<Vec<i32>, usize>,
+    transitions: Vec<OnceLock<Vec<(usize, f64)>>>,
+    outcome_keeps: Vec<OnceLock<Vec<usize>>>,
+}
+
+impl Outcomes {
+    pub fn new(dice_count: usize, faces: i32) -> Outcomes {
+        let outcomes = multisets(dice_count, faces);
+        let outcome_index = outcomes.iter().enumerate().map(|(i, o)| (o.clone(), i)).collect::<HashMap<_, _>>();
+        let keeps = (0..=dice_count).flat_map(|n| multisets(n, faces)).collect::<Vec<_>>();
+        let keep_index = keeps.iter().enumerate().map(|(i, k)| (k.clone(), i)).collect::<HashMap<_, _>>();
+
+        Outcomes {
+            dice_count,
+            faces,
+            transitions: keeps.iter().map(|_| OnceLock::new()).collect(),
+            outcome_keeps: outcomes.iter().map(|_| OnceLock::new()).collect(),
+            outcomes,
+            outcome_index,
+            keeps,
+            keep_index,
+        }
+    }
+
+    pub fn dice_count(&self) -> usize {
+        self.dice_count
+    }
+
+    pub fn faces(&self) -> i32 {
+        self.faces
+    }
+
+    pub fn outcomes(&self) -> &[Vec<i32>] {
+        &self.outcomes
+    }
+
+    pub fn keeps(&self) -> &[Vec<i32>] {
+        &self.keeps
+    }
+
+    // The outcome of a roll, in any order
+    pub fn outcome(&self, dice: &[i32]) -> Option<usize> {
+        self.outcome_index.get(&dice.iter().copied().sorted().collect::<Vec<_>>()).copied()
+    }
+
+    // The keep for holding these dice, in any order
+    pub fn keep(&self, dice: &[i32]) -> Option<usize> {
+        self.keep_index.get(&dice.iter().copied().sorted().collect::<Vec<_>>()).copied()
+    }
+
+    // The outcomes of holding the keep and rerolling the other dice, with their probability
+    pub fn transitions(&self, keep: usize) -> &[(usize, f64)] {
+        self.transitions[keep].get_or_init(|| {
+            let held = &self.keeps[keep];
+            multisets(self.dice_count - held.len(), self.faces).into_iter().map(|roll| {
+                let outcome = held.iter().chain(roll.iter()).copied().sorted().collect::<Vec<_>>();
+                (self.outcome_index[&outcome], probability(&roll, self.faces))
+            }).collect()
+        })
+    }
+
+    // The distribution over rolls after holding the kept dice and rerolling the
+    // given number, or None if they don't add up to a full roll
+    pub fn distribution(&self, kept: &[i32], rerolled: usize) -> Option<Vec<(&[i32], f64)>> {
+        if kept.len() + rerolled != self.dice_count {
+            return None;
+        }
+        let keep = self.keep(kept)?;
+        Some(self.transitions(keep).iter().map(|&(o, p)| (self.outcomes[o].as_slice(), p)).collect())
+    }
+
+    // The distinct keeps that can be held from the outcome
+    pub fn outcome_keeps(&self, outcome: usize) -> &[usize] {
+        self.outcome_keeps[outcome].get_or_init(|| {
+            let dice = &self.outcomes[outcome];
+            (0..1 << self.dice_count).map(|subset| self.keep_index[&hold(dice, subset)]).sorted().dedup().collect()
+        })
+    }
+
+    // The expected value of holding each keep and rerolling the rest, given a value for every outcome
+    pub fn expected_values(&self, values: &[f64]) -> Vec<f64> {
+        (0..self.keeps.len())
+            .map(|k| self.transitions(k).iter().map(|&(o, p)| p * values[o]).sum())
+            .collect()
+    }
+
+    // The value of each outcome with the given rerolls left, holding the best dice each time
+    pub fn roll_values(&self, final_values: Vec<f64>, rerolls: usize) -> Vec<f64> {
+        let mut values = final_values;
+        for _ in 0..rerolls {
+            let keep_values = self.expected_values(&values);
+            values = (0..self.outcomes.len())
+                .map(|o| self.outcome_keeps(o).iter().map(|&k| keep_values[k]).fold(f64::MIN, f64::max))
+                .collect();
+        }
+        values
+    }
+}
+
+// All sorted rolls of n dice
+fn multisets(n: usize, faces: i32) -> Vec<Vec<i32>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+    multisets(n - 1, faces).into_iter()
+        .flat_map(|rest| {
+            let lowest = rest.first().copied().unwrap_or(faces);
+            (1..=lowest).map(move |v| {
+                let mut dice = vec![v];
+                dice.extend(&rest);
+                dice
+            })
+        })
+        .collect()
+}
+
+// The chance of rolling exactly these dice, in any order
+fn probability(roll: &[i32], faces: i32) -> f64 {
+    let factorial = |n: usize| (1..=n).product::<usize>() as f64;
+    let orders = roll.iter().counts().values().fold(factorial(roll.len()), |total, &count| total / factorial(count));
+    orders / (faces as f64).powi(roll.len() as i32)
+}
+
+// The dice in the subset of positions, sorted
+fn hold(dice: &[i32], subset: usize) -> Vec<i32> {
+    dice.iter().enumerate()
+        .filter(|&(i, _)| subset & (1 << i) != 0)
+        .map(|(_, &d)| d)
+        .sorted()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcomes() {
+        let outcomes = Outcomes::new(5, 6);
+        assert_eq!(outcomes.outcomes().len(), 252);
+        assert_eq!(outcomes.keeps().len(), 462);
+        assert_eq!(outcomes.outcome(&[6, 1, 3, 3, 2]), outcomes.outcome(&[1, 2, 3, 3, 6]));
+        for keep in 0..outcomes.keeps().len() {
+            assert!((outcomes.transitions(keep).iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(outcomes.outcome_keeps(outcomes.outcome(&[1, 1, 1, 1, 1]).unwrap()).len(), 6);
+    }
+
+    #[test]
+    fn test_distribution() {
+        let outcomes = Outcomes::new(5, 6);
+        assert!(outcomes.distribution(&[6, 6], 2).is_none());
+
+        let distribution = outcomes.distribution(&[6, 6, 6], 2).unwrap();
+        assert_eq!(distribution.len(), 21);
+        let yatsy = distribution.iter().find(|(dice, _)| *dice == [6, 6, 6, 6, 6]).unwrap().1;
+        assert!((yatsy - 1.0 / 36.0).abs() < 1e-12);
+        let full_house = distribution.iter().find(|(dice, _)| *dice == [1, 2, 6, 6, 6]).unwrap().1;
+        assert!((full_house - 2.0 / 36.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,200 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::game;
+
+const START_RATING: f64 = 1500.0;
+const K_FACTOR: f64 = 32.0;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Profile {
+    pub name: String,
+    pub games: u32,
+    pub wins: u32,
+    pub rating: f64,
+    #[serde(default)]
+    pub head_to_head: BTreeMap<String, Record>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub struct Record {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+// Player profiles with Elo ratings, kept in a JSON file
+pub struct Profiles {
+    path: PathBuf,
+    profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    // A missing file is an empty list of profiles
+    pub fn load(path: impl AsRef<Path>) -> Result<Profiles, String> {
+        let path = path.as_ref().to_path_buf();
+        let profiles = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|e| format!("Invalid profiles in {}: {}", path.display(), e))?,
+            Err(_) if !path.exists() => Vec::new(),
+            Err(e) => return Err(format!("Could not read {}: {}", path.display(), e)),
+        };
+        Ok(Profiles { path, profiles })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(&self.profiles).map_err(|e| e.to_string())?;
+        fs::write(&self.path, contents).map_err(|e| format!("Could not write {}: {}", self.path.display(), e))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    pub fn add(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() || name.contains(',') {
+            return Err("Profile names can't be empty or contain commas".to_string());
+        }
+        if self.get(name).is_some() {
+            return Err(format!("There already is a profile for {}", name));
+        }
+
+        self.profiles.push(Profile {
+            name: name.to_string(),
+            games: 0,
+            wins: 0,
+            rating: START_RATING,
+            head_to_head: BTreeMap::new(),
+        });
+        Ok(())
+    }
+
+    // Highest rating first
+    pub fn ranking(&self) -> Vec<&Profile> {
+        let mut profiles = self.profiles.iter().collect::<Vec<_>>();
+        profiles.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+        profiles
+    }
+
+    // Picks players by name, or by their number in the ranking
+    pub fn choose(&self, players: &[String]) -> Result<Vec<String>, String> {
+        let ranking = self.ranking();
+        players.iter().map(|player| {
+            let profile = match player.parse::<usize>() {
+                Ok(n) if n > 0 => ranking.get(n - 1).copied(),
+                _ => self.get(player),
+            };
+            profile.map(|p| p.name.clone())
+                .ok_or(format!("There is no profile for {}, add one with: yatsy profiles add <name>", player))
+        }).collect()
+    }
+
+    // Updates the ratings and records of everyone in a finished multiplayer game.
+    // Every pair of players counts as a match, and rating changes are split over
+    // the number of opponents so a bigger game isn't worth more.
+    pub fn record_game(&mut self, game: &game::Game) {
+        let ranking = game.ranking().into_iter()
+            .filter(|(_, p)| self.get(&p.name).is_some())
+            .map(|(rank, p)| (rank, p.name.clone()))
+            .collect::<Vec<_>>();
+        if ranking.len() < 2 {
+            return;
+        }
+
+        let ratings = ranking.iter().map(|(_, name)| self.get(name).unwrap().rating).collect::<Vec<_>>();
+        let opponents = (ranking.len() - 1) as f64;
+        for (i, (rank, name)) in ranking.iter().enumerate() {
+            let mut change = 0.0;
+            let mut records = Vec::new();
+            for (j, (other_rank, other)) in ranking.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                // A lower rank is a better result
+                let result = other_rank.cmp(rank);
+                let score = match result {
+                    Ordering::Greater => 1.0,
+                    Ordering::Equal => 0.5,
+                    Ordering::Less => 0.0,
+                };
+                change += K_FACTOR / opponents * (score - expected_score(ratings[i], ratings[j]));
+                records.push((other.clone(), result));
+            }
+
+            let profile = self.profiles.iter_mut().find(|p| &p.name == name).unwrap();
+            profile.games += 1;
+            if *rank == 1 {
+                profile.wins += 1;
+            }
+            profile.rating += change;
+            for (other, result) in records {
+                let record = profile.head_to_head.entry(other).or_default();
+                match result {
+                    Ordering::Greater => record.wins += 1,
+                    Ordering::Equal => record.draws += 1,
+                    Ordering::Less => record.losses += 1,
+                }
+            }
+        }
+    }
+
+    pub fn format_ranking(&self) -> String {
+        let mut lines = vec!["#\tRating\tGames\tWins\tPlayer".to_string()];
+        for (i, p) in self.ranking().iter().enumerate() {
+            lines.push(format!("{}\t{:.0}\t{}\t{}\t{}", i + 1, p.rating, p.games, p.wins, p.name));
+        }
+        lines.join("\n")
+    }
+
+    pub fn format_profile(&self, name: &str) -> Option<String> {
+        let profile = self.get(name)?;
+        let mut lines = vec![
+            format!("{}: rating {:.0}, {} games, {} wins", profile.name, profile.rating, profile.games, profile.wins),
+            "Won\tLost\tDrawn\tAgainst".to_string(),
+        ];
+        for (other, record) in &profile.head_to_head {
+            lines.push(format!("{}\t{}\t{}\t{}", record.wins, record.losses, record.draws, other));
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+// The chance of winning against the other rating, counting a draw as half
+fn expected_score(rating: f64, other: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((other - rating) / 400.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratings() {
+        let path = std::env::temp_dir().join(format!("yatsy-profiles-{}.json", std::process::id()));
+        let mut profiles = Profiles::load(&path).unwrap();
+        for name in ["Kim", "Anna", "Bob"] {
+            profiles.add(name).unwrap();
+        }
+        assert!(profiles.add("Kim").is_err());
+        assert_eq!(profiles.choose(&["Anna".to_string(), "1".to_string()]).unwrap(), vec!["Anna", "Kim"]);
+        assert!(profiles.choose(&["Eve".to_string()]).is_err());
+
+        let names = ["Kim", "Anna", "Bob"].iter().map(|n| n.to_string()).collect();
+        profiles.record_game(&game::finished_game(names, |name| if name == "Anna" { 25 } else { 20 }));
+        let anna = profiles.get("Anna").unwrap();
+        assert_eq!((anna.games, anna.wins), (1, 1));
+        assert_eq!(anna.rating, START_RATING + 16.0);
+        assert_eq!(profiles.get("Kim").unwrap().rating, START_RATING - 8.0);
+        assert_eq!(profiles.get("Kim").unwrap().head_to_head["Bob"], Record { wins: 0, losses: 0, draws: 1 });
+        assert_eq!(profiles.get("Bob").unwrap().head_to_head["Anna"], Record { wins: 0, losses: 1, draws: 0 });
+
+        profiles.save().unwrap();
+        let loaded = Profiles::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.ranking()[0], profiles.get("Anna").unwrap());
+    }
+}
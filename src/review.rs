@@ -0,0 +1,194 @@
+use super::dice::DiceRoll;
+use super::game::{Action, Decision, Game, Pick};
+use super::solver::Solver;
+
+// Anything closer to the best choice than this is rounding
+const TOLERANCE: f64 = 0.01;
+
+#[derive(Debug, Clone)]
+pub struct Mistake {
+    pub turn: usize,
+    pub dice: DiceRoll,
+    pub rerolls: i32,
+    pub made: String,
+    pub best: String,
+    // Expected points lost compared to the best choice
+    pub loss: f64,
+}
+
+// How one player's choices compare to the strategy with the highest expected score
+#[derive(Debug, Clone)]
+pub struct Review {
+    pub player: String,
+    pub decisions: usize,
+    pub loss: f64,
+    // Worst first
+    pub mistakes: Vec<Mistake>,
+}
+
+impl Review {
+    pub fn loss_per_decision(&self) -> f64 {
+        if self.decisions == 0 {
+            return 0.0;
+        }
+        self.loss / self.decisions as f64
+    }
+
+    // The share of choices that were as good as the best one, in percent
+    pub fn accuracy(&self) -> f64 {
+        if self.decisions == 0 {
+            return 100.0;
+        }
+        100.0 * (self.decisions - self.mistakes.len()) as f64 / self.decisions as f64
+    }
+
+    pub fn format(&self) -> String {
+        let mut lines = vec![
+            format!("--- Review for {} ---", self.player),
+            format!("{} decisions, {:.1}% accurate, {:.2}p lost per decision ({:.1}p in total)",
+                self.decisions, self.accuracy(), self.loss_per_decision(), self.loss),
+        ];
+        if !self.mistakes.is_empty() {
+            lines.push("Worst mistakes:".to_string());
+        }
+        for m in self.mistakes.iter().take(3) {
+            lines.push(format!("-{:.1}p\tTurn {}, {} with {} rerolls left: {}, best was {}",
+                m.loss, m.turn, m.dice, m.rerolls, m.made, m.best));
+        }
+        lines.join("\n")
+    }
+}
+
+// Replays every reroll and pick of the game against the solver. Picking with
+// rerolls left counts as choosing to hold everything as well.
+pub fn review(game: &Game, solver: &mut Solver) -> Vec<Review> {
+    let mut reviews = game.players.iter()
+        .map(|p| Review { player: p.name.clone(), decisions: 0, loss: 0.0, mistakes: Vec::new() })
+        .collect::<Vec<_>>();
+    let mut turns = vec![1; game.players.len()];
+
+    for decision in game.history() {
+        let all = (0..decision.dice.dice.len()).collect::<Vec<_>>();
+        let mut judged = Vec::new();
+        match &decision.action {
+            Action::Reroll(indices) => {
+                let held = all.iter().copied().filter(|i| !indices.contains(i)).collect::<Vec<_>>();
+                judged.push(judge_keep(solver, decision, &held));
+            },
+            Action::Pick(pick, _) => {
+                if decision.rerolls > 0 {
+                    judged.push(judge_keep(solver, decision, &all));
+                }
+                judged.push(judge_pick(game, solver, decision, *pick));
+            },
+        }
+
+        let review = &mut reviews[decision.player];
+        for (made, best, loss) in judged.into_iter().flatten() {
+            review.decisions += 1;
+            review.loss += loss;
+            if loss > TOLERANCE {
+                review.mistakes.push(Mistake {
+                    turn: turns[decision.player],
+                    dice: decision.dice.clone(),
+                    rerolls: decision.rerolls,
+                    made,
+                    best,
+                    loss,
+                });
+            }
+        }
+        if let Action::Pick(..) = decision.action {
+            turns[decision.player] += 1;
+        }
+    }
+
+    for review in reviews.iter_mut() {
+        review.mistakes.sort_by(|a, b| b.loss.total_cmp(&a.loss));
+    }
+    reviews
+}
+
+// What was done and what was best, with the expected points lost
+fn judge_keep(solver: &mut Solver, decision: &Decision, held: &[usize]) -> Option<(String, String, f64)> {
+    let values = |held: &[usize]| {
+        let mut values = held.iter().map(|&i| decision.dice.dice[i]).collect::<Vec<_>>();
+        values.sort();
+        values
+    };
+    let choices = solver.keep_values(&decision.dice, decision.rerolls, &decision.card);
+    let (_, made) = choices.iter().find(|(h, _)| values(h) == values(held))?;
+    let (best_held, best) = &choices[0];
+    Some((decision.dice.format_hold(held), decision.dice.format_hold(best_held), best - made))
+}
+
+fn judge_pick(game: &Game, solver: &mut Solver, decision: &Decision, pick: Pick) -> Option<(String, String, f64)> {
+    let choices = solver.category_values(&decision.dice, &decision.card);
+    let made = match pick {
+        Pick::Result(t) => choices.iter().find(|&&(c, _)| c == t)?.1,
+        // The row is filled in without the dice
+        Pick::Zero(t) | Pick::Strike(t) => {
+            let mut card = decision.card.clone();
+            card.strike(t, 0);
+            solver.expected_score(&card)
+        },
+    };
+    let (best_type, best) = choices[0];
+    let best_pick = match game.ruleset.score(best_type, &decision.dice) {
+        0 => Pick::Zero(best_type),
+        _ => Pick::Result(best_type),
+    };
+    Some((describe_pick(game, pick), describe_pick(game, best_pick), best - made))
+}
+
+fn describe_pick(game: &Game, pick: Pick) -> String {
+    let name = game.ruleset.category_name(pick.result_type());
+    match pick {
+        Pick::Result(_) => format!("Picked {}", name),
+        Pick::Zero(_) => format!("Scored 0 in {}", name),
+        Pick::Strike(_) => format!("Struck {}", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice_result::ResultType;
+    use crate::ruleset::{self, Ruleset};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_review() {
+        let ruleset: Arc<dyn Ruleset> = Arc::new(ruleset::Scandinavian);
+        let mut solver = Solver::new(ruleset.clone(), false).unwrap();
+        let mut game = Game::new(ruleset.clone(), false, vec!["Kim".to_string()]).with_seed(3, false);
+        for result_type in ruleset.categories() {
+            if ![ResultType::Chance, ResultType::Yatsy].contains(&result_type) {
+                game.players[0].score_card.strike(result_type, 0);
+            }
+        }
+
+        // Giving up Chance straight away, then the best play
+        game.place(Pick::Strike(ResultType::Chance), 0).unwrap();
+        loop {
+            let (held, _) = solver.best_keep(&game.dice, game.rerolls, game.score_card());
+            if held.len() == 5 {
+                break;
+            }
+            game.reroll((0..5).filter(|i| !held.contains(i)).collect()).unwrap();
+        }
+        let pick = match game.available_results().is_empty() {
+            true => Pick::Zero(ResultType::Yatsy),
+            false => Pick::Result(ResultType::Yatsy),
+        };
+        game.place(pick, 0).unwrap();
+        assert!(game.is_over());
+
+        let review = review(&game, &mut solver).remove(0);
+        assert!(review.accuracy() < 100.0);
+        assert!(review.mistakes.iter().all(|m| m.turn == 1));
+        assert_eq!(review.mistakes[0].made, "Struck Chance");
+        assert_eq!(review.mistakes[0].best, "Scored 0 in Yatsy");
+        assert!(review.mistakes[0].loss > 10.0);
+    }
+}
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use super::dice;
+use super::dice_result::ResultType;
+use super::scores::ScoreCard;
+
+mod balut;
+mod custom;
+mod generala;
+mod kniffel;
+mod maxi;
+mod triple;
+mod yahtzee;
+
+pub use balut::Balut;
+pub use custom::Custom;
+pub use generala::Generala;
+pub use kniffel::Kniffel;
+pub use maxi::Maxi;
+pub use triple::Triple;
+pub use yahtzee::Yahtzee;
+
+// A ruleset owns everything that differs between Yatsy variants: which
+// categories are on the score card, how they are scored and how the
+// upper section bonus is awarded.
+pub trait Ruleset: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn dice_count(&self) -> usize {
+        5
+    }
+
+    fn faces(&self) -> i32 {
+        6
+    }
+
+    /// The categories on the score card, in the order they are printed.
+    fn categories(&self) -> Vec<ResultType>;
+
+    /// The name of the category as printed on the score card.
+    fn category_name(&self, result_type: ResultType) -> String {
+        result_type.to_string()
+    }
+
+    /// The score for placing the dice in the given category, 0 if they don't fit it.
+    fn score(&self, result_type: ResultType, dice: &dice::DiceRoll) -> i32;
+
+    /// Whether the category belongs to the upper section and counts towards the bonus.
+    fn is_upper(&self, result_type: ResultType) -> bool {
+        matches!(result_type,
+            ResultType::Ones | ResultType::Twos | ResultType::Threes |
+            ResultType::Fours | ResultType::Fives | ResultType::Sixes)
+    }
+
+    fn bonus_threshold(&self) -> i32 {
+        63
+    }
+
+    fn bonus_amount(&self) -> i32 {
+        50
+    }
+
+    fn rerolls_per_turn(&self) -> i32 {
+        2
+    }
+
+    /// Number of columns on the score card, every category is filled once per column.
+    fn columns(&self) -> usize {
+        1
+    }
+
+    /// What the total of the column is multiplied by.
+    fn column_multiplier(&self, _column: usize) -> i32 {
+        1
+    }
+
+    /// The open categories the dice may be placed in, including by striking.
+    fn open_types(&self, _dice: &dice::DiceRoll, card: &ScoreCard) -> Vec<ResultType> {
+        card.get_available_types()
+    }
+
+    /// The score for the dice after the given number of rerolls, for rules where it matters.
+    fn score_turn(&self, result_type: ResultType, dice: &dice::DiceRoll, _rerolls_used: i32) -> i32 {
+        self.score(result_type, dice)
+    }
+
+    /// The score for placing the dice in the given category, for rules where it depends on the card.
+    fn score_on_card(&self, result_type: ResultType, dice: &dice::DiceRoll, rerolls_used: i32, _card: &ScoreCard) -> i32 {
+        self.score_turn(result_type, dice, rerolls_used)
+    }
+
+    /// Whether the dice win the game outright.
+    fn is_instant_win(&self, _dice: &dice::DiceRoll, _rerolls_used: i32) -> bool {
+        false
+    }
+
+    /// Points awarded on top of the category score when the dice are placed.
+    fn extra_bonus(&self, _dice: &dice::DiceRoll, _card: &ScoreCard) -> i32 {
+        0
+    }
+
+    /// Points awarded for the finished lines of the card, for rules where the final
+    /// score is a point total instead of the card total. None by default.
+    fn score_points(&self, _card: &ScoreCard) -> Option<Vec<(String, i32)>> {
+        None
+    }
+
+    /// Whether rerolls left over at the end of a turn carry over to the next one.
+    fn saves_rerolls(&self) -> bool {
+        false
+    }
+}
+
+pub fn get_ruleset(name: &str) -> Option<Arc<dyn Ruleset>> {
+    match name {
+        "scandinavian" | "yatzy" => Some(Arc::new(Scandinavian)),
+        "maxi" => Some(Arc::new(Maxi)),
+        "yahtzee" => Some(Arc::new(Yahtzee)),
+        "triple" => Some(Arc::new(Triple)),
+        "kniffel" => Some(Arc::new(Kniffel)),
+        "generala" => Some(Arc::new(Generala)),
+        "balut" => Some(Arc::new(Balut)),
+        _ => None
+    }
+}
+
+pub struct Scandinavian;
+
+impl Ruleset for Scandinavian {
+    fn name(&self) -> &str {
+        "Scandinavian Yatzy"
+    }
+
+    fn categories(&self) -> Vec<ResultType> {
+        vec![
+            ResultType::Ones,
+            ResultType::Twos,
+            ResultType::Threes,
+            ResultType::Fours,
+            ResultType::Fives,
+            ResultType::Sixes,
+            ResultType::Pair,
+            ResultType::TwoPairs,
+            ResultType::ThreeOfAKind,
+            ResultType::FourOfAKind,
+            ResultType::SmallStraight,
+            ResultType::LargeStraight,
+            ResultType::FullHouse,
+            ResultType::Chance,
+            ResultType::Yatsy,
+        ]
+    }
+
+    fn score(&self, result_type: ResultType, dice: &dice::DiceRoll) -> i32 {
+        match result_type {
+            ResultType::Ones => dice.ones(),
+            ResultType::Twos => dice.twos(),
+            ResultType::Threes => dice.threes(),
+            ResultType::Fours => dice.fours(),
+            ResultType::Fives => dice.fives(),
+            ResultType::Sixes => dice.sixes(),
+            ResultType::Pair => dice.pair(),
+            ResultType::TwoPairs => dice.two_pairs(),
+            ResultType::ThreeOfAKind => dice.three_of_a_kind(),
+            ResultType::FourOfAKind => dice.four_of_a_kind(),
+            ResultType::SmallStraight => dice.small_straight(),
+            ResultType::LargeStraight => dice.large_straight(),
+            ResultType::FullHouse => dice.full_house(),
+            ResultType::Chance => dice.chance(),
+            ResultType::Yatsy => dice.yatsy(),
+            _ => 0,
+        }
+    }
+}
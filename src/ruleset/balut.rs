@@ -0,0 +1,151 @@
+use super::Ruleset;
+use crate::dice;
+use crate::dice_result::ResultType;
+use crate::scores::{Cell, ScoreCard};
+
+// Balut has seven categories that are each filled four times. When the card
+// is done, the category totals and the overall score are turned into points,
+// and the points decide the game.
+pub struct Balut;
+
+// Category totals needed for points, as (category, total, points)
+const THRESHOLDS: [(ResultType, i32, i32); 4] = [
+    (ResultType::Fours, 52, 2),
+    (ResultType::Fives, 65, 2),
+    (ResultType::Sixes, 78, 2),
+    (ResultType::Chance, 100, 2),
+];
+
+// Points for the overall score, as (lowest score, points)
+const SCORE_LADDER: [(i32, i32); 7] = [
+    (550, 4),
+    (500, 3),
+    (450, 2),
+    (400, 1),
+    (350, 0),
+    (300, -1),
+    (0, -2),
+];
+
+impl Balut {
+    // Straights and full houses give points when none of their slots is zero or struck
+    fn all_made(&self, card: &ScoreCard, result_type: ResultType) -> bool {
+        card.get_cells(result_type).iter().all(|c| matches!(c, Cell::Scored(score) if *score > 0))
+    }
+}
+
+impl Ruleset for Balut {
+    fn name(&self) -> &str {
+        "Balut"
+    }
+
+    fn columns(&self) -> usize {
+        4
+    }
+
+    fn bonus_amount(&self) -> i32 {
+        0
+    }
+
+    fn is_upper(&self, _result_type: ResultType) -> bool {
+        false
+    }
+
+    fn categories(&self) -> Vec<ResultType> {
+        vec![
+            ResultType::Fours,
+            ResultType::Fives,
+            ResultType::Sixes,
+            ResultType::Straight,
+            ResultType::FullHouse,
+            ResultType::Chance,
+            ResultType::Yatsy,
+        ]
+    }
+
+    fn category_name(&self, result_type: ResultType) -> String {
+        match result_type {
+            ResultType::Chance => "Choice".to_string(),
+            ResultType::Yatsy => "Balut".to_string(),
+            _ => result_type.to_string(),
+        }
+    }
+
+    fn score(&self, result_type: ResultType, dice: &dice::DiceRoll) -> i32 {
+        match result_type {
+            ResultType::Straight => dice.small_straight().max(dice.large_straight()),
+            ResultType::FullHouse if dice.full_house() > 0 => dice.chance(),
+            ResultType::Yatsy if dice.yatsy() > 0 => 20 + dice.chance(),
+            ResultType::FullHouse | ResultType::Yatsy => 0,
+            _ => super::Scandinavian.score(result_type, dice),
+        }
+    }
+
+    fn score_points(&self, card: &ScoreCard) -> Option<Vec<(String, i32)>> {
+        let mut points = Vec::<(String, i32)>::new();
+        let label = |result_type: ResultType| {
+            format!("{} ({}p)", self.category_name(result_type), card.get_category_total(result_type))
+        };
+
+        for (result_type, total, p) in THRESHOLDS {
+            if card.get_category_total(result_type) >= total {
+                points.push((label(result_type), p));
+            }
+        }
+
+        if self.all_made(card, ResultType::Straight) {
+            points.push((label(ResultType::Straight), 4));
+        }
+        if self.all_made(card, ResultType::FullHouse) {
+            points.push((label(ResultType::FullHouse), 3));
+        }
+
+        let baluts = card.get_cells(ResultType::Yatsy).iter()
+            .filter(|c| matches!(c, Cell::Scored(score) if *score > 0))
+            .count() as i32;
+        if baluts > 0 {
+            points.push((label(ResultType::Yatsy), baluts * 2));
+        }
+
+        // The score ladder only applies once the card is done
+        if card.is_complete() {
+            let score = card.get_card_total();
+            let (_, p) = SCORE_LADDER.iter().find(|(lowest, _)| score >= *lowest).unwrap();
+            points.push((format!("Score ({}p)", score), *p));
+        }
+
+        Some(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice_result::{get_results, DiceResult};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_scores() {
+        let results = get_results(&Balut, &dice::DiceRoll { dice: vec![5, 5, 5, 5, 5], faces: 6 }, 0);
+        assert_eq!(results, vec![
+            DiceResult { result_type: ResultType::Fives, score: 25 },
+            DiceResult { result_type: ResultType::Chance, score: 25 },
+            DiceResult { result_type: ResultType::Yatsy, score: 45 },
+        ]);
+    }
+
+    #[test]
+    fn test_points() {
+        let mut card = ScoreCard::new(Arc::new(Balut));
+        for _ in 0..4 {
+            let column = card.get_open_columns(ResultType::Sixes)[0];
+            card.add_result(DiceResult { result_type: ResultType::Sixes, score: 24 }, column);
+            card.add_result(DiceResult { result_type: ResultType::Straight, score: 20 }, column);
+        }
+        card.add_result(DiceResult { result_type: ResultType::Yatsy, score: 50 }, 0);
+
+        // Sixes 96p, four straights and one Balut
+        assert_eq!(card.get_total(), 2 + 4 + 2);
+        assert_eq!(card.get_card_total(), 96 + 80 + 50);
+    }
+}
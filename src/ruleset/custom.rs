@@ -0,0 +1,393 @@
+use std::fs;
+
+use serde::Deserialize;
+
+use super::Ruleset;
+use crate::dice;
+use crate::dice_result::ResultType;
+
+// A ruleset defined in a TOML or JSON rules file, with categories built from
+// a small set of patterns. See rules/example.toml for the format.
+pub struct Custom {
+    name: String,
+    dice: usize,
+    faces: i32,
+    rerolls: i32,
+    bonus_threshold: i32,
+    bonus: i32,
+    categories: Vec<Category>,
+}
+
+struct Category {
+    name: String,
+    pattern: Pattern,
+    points: Points,
+    upper: bool,
+}
+
+enum Pattern {
+    Face(i32),
+    OfAKind(usize),
+    Straight(usize),
+    FullHouse,
+    Chance,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(untagged)]
+enum Points {
+    Fixed(i32),
+    Kind(PointsKind),
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum PointsKind {
+    // Sum of the dice that make up the pattern
+    Matched,
+    // Sum of all dice
+    Sum,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    name: String,
+    #[serde(default = "default_dice")]
+    dice: usize,
+    #[serde(default = "default_faces")]
+    faces: i32,
+    #[serde(default = "default_rerolls")]
+    rerolls: i32,
+    #[serde(default)]
+    bonus_threshold: i32,
+    #[serde(default)]
+    bonus: i32,
+    categories: Vec<CategoryDef>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CategoryDef {
+    name: String,
+    pattern: PatternKind,
+    value: Option<i32>,
+    count: Option<usize>,
+    length: Option<usize>,
+    score: Option<Points>,
+    upper: Option<bool>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum PatternKind {
+    Face,
+    OfAKind,
+    Straight,
+    FullHouse,
+    Chance,
+}
+
+fn default_dice() -> usize {
+    5
+}
+
+fn default_faces() -> i32 {
+    6
+}
+
+fn default_rerolls() -> i32 {
+    2
+}
+
+impl Custom {
+    pub fn load(path: &str) -> Result<Custom, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        let result = if path.ends_with(".json") {
+            Custom::from_json(&contents)
+        } else {
+            Custom::from_toml(&contents)
+        };
+        result.map_err(|e| format!("Invalid rules in {}: {}", path, e))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Custom, String> {
+        let file = toml::from_str::<RulesFile>(contents).map_err(|e| e.to_string())?;
+        Custom::validate(file)
+    }
+
+    pub fn from_json(contents: &str) -> Result<Custom, String> {
+        let file = serde_json::from_str::<RulesFile>(contents).map_err(|e| e.to_string())?;
+        Custom::validate(file)
+    }
+
+    fn validate(file: RulesFile) -> Result<Custom, String> {
+        if !(1..=10).contains(&file.dice) {
+            return Err("dice must be between 1 and 10".to_string());
+        }
+        if !(2..=20).contains(&file.faces) {
+            return Err("faces must be between 2 and 20".to_string());
+        }
+        if file.rerolls < 0 {
+            return Err("rerolls can't be negative".to_string());
+        }
+        if file.bonus < 0 || file.bonus_threshold < 0 {
+            return Err("bonus and bonus_threshold can't be negative".to_string());
+        }
+        // Without a threshold every card would get the bonus before a single roll
+        if file.bonus > 0 && file.bonus_threshold <= 0 {
+            return Err("a bonus needs a bonus_threshold above 0".to_string());
+        }
+        if file.categories.is_empty() || file.categories.len() > u8::MAX as usize {
+            return Err(format!("there must be between 1 and {} categories", u8::MAX));
+        }
+
+        let mut categories = Vec::<Category>::new();
+        for def in file.categories {
+            if def.name.trim().is_empty() {
+                return Err("categories must have a name".to_string());
+            }
+            if categories.iter().any(|c| c.name == def.name) {
+                return Err(format!("category '{}' is defined twice", def.name));
+            }
+            let category = Custom::validate_category(&def, file.dice, file.faces)
+                .map_err(|e| format!("category '{}': {}", def.name, e))?;
+            categories.push(category);
+        }
+
+        Ok(Custom {
+            name: file.name,
+            dice: file.dice,
+            faces: file.faces,
+            rerolls: file.rerolls,
+            bonus_threshold: file.bonus_threshold,
+            bonus: file.bonus,
+            categories,
+        })
+    }
+
+    fn validate_category(def: &CategoryDef, dice: usize, faces: i32) -> Result<Category, String> {
+        // Only the key of the pattern itself may be set
+        let keys = [("value", def.value.is_some()), ("count", def.count.is_some()), ("length", def.length.is_some())];
+        let allowed = match def.pattern {
+            PatternKind::Face => Some("value"),
+            PatternKind::OfAKind => Some("count"),
+            PatternKind::Straight => Some("length"),
+            PatternKind::FullHouse | PatternKind::Chance => None,
+        };
+        if let Some((key, _)) = keys.iter().find(|&&(key, set)| set && Some(key) != allowed) {
+            return Err(format!("{} doesn't apply to this pattern", key));
+        }
+
+        let pattern = match def.pattern {
+            PatternKind::Face => match def.value {
+                Some(value) if (1..=faces).contains(&value) => Pattern::Face(value),
+                _ => return Err(format!("face needs a value between 1 and {}", faces)),
+            },
+            PatternKind::OfAKind => match def.count {
+                Some(count) if (2..=dice).contains(&count) => Pattern::OfAKind(count),
+                _ => return Err(format!("of_a_kind needs a count between 2 and {}", dice)),
+            },
+            PatternKind::Straight => {
+                let longest = dice.min(faces as usize);
+                match def.length {
+                    Some(length) if (2..=longest).contains(&length) => Pattern::Straight(length),
+                    _ => return Err(format!("straight needs a length between 2 and {}", longest)),
+                }
+            },
+            PatternKind::FullHouse if dice < 5 => return Err("full_house needs at least 5 dice".to_string()),
+            PatternKind::FullHouse => Pattern::FullHouse,
+            PatternKind::Chance => Pattern::Chance,
+        };
+
+        let points = def.score.unwrap_or(Points::Kind(PointsKind::Matched));
+        if let Points::Fixed(score) = points && score <= 0 {
+            return Err("a fixed score must be positive".to_string());
+        }
+
+        Ok(Category {
+            name: def.name.clone(),
+            upper: def.upper.unwrap_or(matches!(pattern, Pattern::Face(_))),
+            pattern,
+            points,
+        })
+    }
+
+    fn get_category(&self, result_type: ResultType) -> Option<&Category> {
+        match result_type {
+            ResultType::Custom(index) => self.categories.get(index as usize),
+            _ => None,
+        }
+    }
+}
+
+impl Category {
+    fn score(&self, dice: &dice::DiceRoll) -> i32 {
+        let Some(matched) = self.matched(dice) else {
+            return 0;
+        };
+
+        match self.points {
+            Points::Fixed(score) => score,
+            Points::Kind(PointsKind::Matched) => matched,
+            Points::Kind(PointsKind::Sum) => dice.dice.iter().sum(),
+        }
+    }
+
+    // Sum of the dice making up the pattern, None if the dice don't fit it
+    fn matched(&self, dice: &dice::DiceRoll) -> Option<i32> {
+        let count = |value: i32| dice.get_count_for_value(value);
+
+        match self.pattern {
+            Pattern::Face(value) => match count(value) {
+                0 => None,
+                n => Some(value * n),
+            },
+            Pattern::OfAKind(n) => match dice.get_value_for_count(n as i32) {
+                0 => None,
+                v => Some(v * n as i32),
+            },
+            Pattern::Straight(length) => (1..=dice.faces).rev()
+                .find(|&v| (0..length as i32).all(|i| count(v - i) > 0))
+                .map(|v| (0..length as i32).map(|i| v - i).sum()),
+            Pattern::FullHouse => match dice.full_house() {
+                0 => None,
+                v => Some(v),
+            },
+            Pattern::Chance => Some(dice.dice.iter().sum()),
+        }
+    }
+}
+
+impl Ruleset for Custom {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn dice_count(&self) -> usize {
+        self.dice
+    }
+
+    fn faces(&self) -> i32 {
+        self.faces
+    }
+
+    fn rerolls_per_turn(&self) -> i32 {
+        self.rerolls
+    }
+
+    fn bonus_threshold(&self) -> i32 {
+        self.bonus_threshold
+    }
+
+    fn bonus_amount(&self) -> i32 {
+        self.bonus
+    }
+
+    fn categories(&self) -> Vec<ResultType> {
+        (0..self.categories.len()).map(|i| ResultType::Custom(i as u8)).collect()
+    }
+
+    fn category_name(&self, result_type: ResultType) -> String {
+        match self.get_category(result_type) {
+            Some(category) => category.name.clone(),
+            None => result_type.to_string(),
+        }
+    }
+
+    fn is_upper(&self, result_type: ResultType) -> bool {
+        self.get_category(result_type).is_some_and(|c| c.upper)
+    }
+
+    fn score(&self, result_type: ResultType, dice: &dice::DiceRoll) -> i32 {
+        self.get_category(result_type).map_or(0, |c| c.score(dice))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice_result::{get_results, DiceResult};
+
+    const RULES: &str = r#"
+        name = "Office Yatzy"
+        dice = 5
+        faces = 8
+        rerolls = 3
+        bonus_threshold = 100
+        bonus = 40
+
+        [[categories]]
+        name = "Eights"
+        pattern = "face"
+        value = 8
+
+        [[categories]]
+        name = "Triple"
+        pattern = "of_a_kind"
+        count = 3
+        score = "sum"
+
+        [[categories]]
+        name = "Straight"
+        pattern = "straight"
+        length = 4
+        score = 30
+
+        [[categories]]
+        name = "House"
+        pattern = "full_house"
+
+        [[categories]]
+        name = "Anything"
+        pattern = "chance"
+    "#;
+
+    #[test]
+    fn test_load_toml() {
+        let rules = Custom::from_toml(RULES).unwrap();
+        assert_eq!(rules.name(), "Office Yatzy");
+        assert_eq!(rules.faces(), 8);
+        assert_eq!(rules.rerolls_per_turn(), 3);
+        assert_eq!(rules.category_name(ResultType::Custom(1)), "Triple");
+        assert!(rules.is_upper(ResultType::Custom(0)));
+        assert!(!rules.is_upper(ResultType::Custom(4)));
+
+        let results = get_results(&rules, &dice::DiceRoll { dice: vec![8, 8, 8, 7, 7], faces: 8 }, 0);
+        assert_eq!(results, vec![
+            DiceResult { result_type: ResultType::Custom(0), score: 24 },
+            DiceResult { result_type: ResultType::Custom(1), score: 38 },
+            DiceResult { result_type: ResultType::Custom(3), score: 38 },
+            DiceResult { result_type: ResultType::Custom(4), score: 38 },
+        ]);
+
+        let results = get_results(&rules, &dice::DiceRoll { dice: vec![5, 6, 7, 8, 1], faces: 8 }, 0);
+        assert!(results.contains(&DiceResult { result_type: ResultType::Custom(2), score: 30 }));
+    }
+
+    #[test]
+    fn test_load_json() {
+        let rules = Custom::from_json(r#"{
+            "name": "Tiny",
+            "dice": 3,
+            "categories": [{ "name": "Pair", "pattern": "of_a_kind", "count": 2 }]
+        }"#).unwrap();
+        assert_eq!(rules.dice_count(), 3);
+        assert_eq!(rules.score(ResultType::Custom(0), &dice::DiceRoll { dice: vec![4, 2, 4], faces: 6 }), 8);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(Custom::from_toml(&RULES.replace("value = 8", "value = 9")).is_err());
+        assert!(Custom::from_toml(&RULES.replace("count = 3", "count = 6")).is_err());
+        assert!(Custom::from_toml(&RULES.replace("length = 4", "length = 6")).is_err());
+        assert!(Custom::from_toml(&RULES.replace("score = 30", "score = -1")).is_err());
+        assert!(Custom::from_toml(&RULES.replace("\"Triple\"", "\"Eights\"")).is_err());
+        assert!(Custom::from_toml(&RULES.replace("pattern = \"chance\"", "pattern = \"chance\"\nbogus = 1")).is_err());
+        assert!(Custom::from_toml(r#"name = "Empty""#).is_err());
+        assert!(Custom::from_toml(&RULES.replace("bonus_threshold = 100", "")).is_err());
+        assert!(Custom::from_toml(&RULES.replace("bonus_threshold = 100\n        bonus = 40", "")).is_ok());
+        assert!(Custom::from_toml(&RULES.replace("count = 3", "count = 3\nvalue = 2")).is_err());
+        assert!(Custom::from_toml(&RULES.replace("pattern = \"chance\"", "pattern = \"chance\"\nlength = 4")).is_err());
+    }
+}
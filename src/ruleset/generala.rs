@@ -0,0 +1,104 @@
+use super::Ruleset;
+use crate::dice;
+use crate::dice_result::{GeneralaName, ResultType};
+use crate::scores::ScoreCard;
+
+// Generala, as played in Latin America. Hands made on the first roll are
+// "servida" and score 5 extra points, and a served Generala wins the game.
+pub struct Generala;
+
+const SERVED_BONUS: i32 = 5;
+
+impl Ruleset for Generala {
+    fn name(&self) -> &str {
+        "Generala"
+    }
+
+    fn bonus_amount(&self) -> i32 {
+        0
+    }
+
+    fn categories(&self) -> Vec<ResultType> {
+        vec![
+            ResultType::Ones,
+            ResultType::Twos,
+            ResultType::Threes,
+            ResultType::Fours,
+            ResultType::Fives,
+            ResultType::Sixes,
+            ResultType::Straight,
+            ResultType::FullHouse,
+            ResultType::FourOfAKind,
+            ResultType::Yatsy,
+            ResultType::DoubleYatsy,
+        ]
+    }
+
+    fn category_name(&self, result_type: ResultType) -> String {
+        GeneralaName(result_type).to_string()
+    }
+
+    fn score(&self, result_type: ResultType, dice: &dice::DiceRoll) -> i32 {
+        match result_type {
+            ResultType::Straight if dice.small_straight() > 0 || dice.large_straight() > 0 => 20,
+            ResultType::FullHouse if dice.full_house() > 0 => 30,
+            ResultType::FourOfAKind if dice.four_of_a_kind() > 0 => 40,
+            ResultType::Yatsy => dice.yatsy(),
+            ResultType::Straight | ResultType::FullHouse | ResultType::FourOfAKind | ResultType::DoubleYatsy => 0,
+            _ => super::Scandinavian.score(result_type, dice),
+        }
+    }
+
+    fn score_turn(&self, result_type: ResultType, dice: &dice::DiceRoll, rerolls_used: i32) -> i32 {
+        let score = self.score(result_type, dice);
+        let served = rerolls_used == 0 && matches!(result_type,
+            ResultType::Straight | ResultType::FullHouse | ResultType::FourOfAKind);
+        if served && score > 0 {
+            return score + SERVED_BONUS;
+        }
+        score
+    }
+
+    fn score_on_card(&self, result_type: ResultType, dice: &dice::DiceRoll, rerolls_used: i32, card: &ScoreCard) -> i32 {
+        // A second Generala only counts once the first one has been scored
+        if result_type == ResultType::DoubleYatsy {
+            if dice.yatsy() > 0 && card.get_score(ResultType::Yatsy, 0) == Some(50) {
+                return 100;
+            }
+            return 0;
+        }
+        self.score_turn(result_type, dice, rerolls_used)
+    }
+
+    fn is_instant_win(&self, dice: &dice::DiceRoll, rerolls_used: i32) -> bool {
+        rerolls_used == 0 && dice.yatsy() > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice_result::{get_available_results, get_results, DiceResult};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_served() {
+        let full = dice::DiceRoll { dice: vec![2, 2, 5, 5, 5], faces: 6 };
+        assert!(get_results(&Generala, &full, 0).contains(&DiceResult { result_type: ResultType::FullHouse, score: 35 }));
+        assert!(get_results(&Generala, &full, 1).contains(&DiceResult { result_type: ResultType::FullHouse, score: 30 }));
+
+        let generala = dice::DiceRoll { dice: vec![4, 4, 4, 4, 4], faces: 6 };
+        assert!(Generala.is_instant_win(&generala, 0));
+        assert!(!Generala.is_instant_win(&generala, 2));
+    }
+
+    #[test]
+    fn test_double_generala() {
+        let mut card = ScoreCard::new(Arc::new(Generala));
+        let generala = dice::DiceRoll { dice: vec![4, 4, 4, 4, 4], faces: 6 };
+        assert!(!get_available_results(&Generala, &generala, 1, &card).iter().any(|r| r.result_type == ResultType::DoubleYatsy));
+
+        card.add_result(DiceResult { result_type: ResultType::Yatsy, score: 50 }, 0);
+        assert!(get_available_results(&Generala, &generala, 1, &card).contains(&DiceResult { result_type: ResultType::DoubleYatsy, score: 100 }));
+    }
+}
@@ -0,0 +1,64 @@
+use super::Ruleset;
+use crate::dice;
+use crate::dice_result::{KniffelName, ResultType};
+
+// The German Kniffel. Scored like Yahtzee with fixed value straights and full
+// house, but without the Joker rules and extra Kniffel bonus.
+pub struct Kniffel;
+
+impl Ruleset for Kniffel {
+    fn name(&self) -> &str {
+        "Kniffel"
+    }
+
+    fn bonus_amount(&self) -> i32 {
+        35
+    }
+
+    fn categories(&self) -> Vec<ResultType> {
+        vec![
+            ResultType::Ones,
+            ResultType::Twos,
+            ResultType::Threes,
+            ResultType::Fours,
+            ResultType::Fives,
+            ResultType::Sixes,
+            ResultType::ThreeOfAKind,
+            ResultType::FourOfAKind,
+            ResultType::FullHouse,
+            ResultType::SmallStraight,
+            ResultType::LargeStraight,
+            ResultType::Yatsy,
+            ResultType::Chance,
+        ]
+    }
+
+    fn category_name(&self, result_type: ResultType) -> String {
+        KniffelName(result_type).to_string()
+    }
+
+    fn score(&self, result_type: ResultType, dice: &dice::DiceRoll) -> i32 {
+        super::Yahtzee.score(result_type, dice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice_result::{get_results, DiceResult};
+
+    #[test]
+    fn test_kniffel() {
+        let results = get_results(&Kniffel, &dice::DiceRoll { dice: vec![3, 4, 5, 6, 6], faces: 6 }, 0);
+        assert_eq!(results, vec![
+            DiceResult { result_type: ResultType::Threes, score: 3 },
+            DiceResult { result_type: ResultType::Fours, score: 4 },
+            DiceResult { result_type: ResultType::Fives, score: 5 },
+            DiceResult { result_type: ResultType::Sixes, score: 12 },
+            DiceResult { result_type: ResultType::SmallStraight, score: 30 },
+            DiceResult { result_type: ResultType::Chance, score: 24 },
+        ]);
+        assert_eq!(Kniffel.category_name(ResultType::SmallStraight), "Kleine Straße");
+        assert_eq!(Kniffel.category_name(ResultType::Yatsy), "Kniffel");
+    }
+}
@@ -0,0 +1,67 @@
+use super::Ruleset;
+use crate::dice;
+use crate::dice_result::ResultType;
+
+// Maxi Yatzy is played with six dice and a few extra categories. Rerolls
+// that aren't used in a turn are saved for later turns.
+pub struct Maxi;
+
+impl Ruleset for Maxi {
+    fn name(&self) -> &str {
+        "Maxi Yatzy"
+    }
+
+    fn dice_count(&self) -> usize {
+        6
+    }
+
+    fn saves_rerolls(&self) -> bool {
+        true
+    }
+
+    fn bonus_threshold(&self) -> i32 {
+        84
+    }
+
+    fn bonus_amount(&self) -> i32 {
+        100
+    }
+
+    fn categories(&self) -> Vec<ResultType> {
+        vec![
+            ResultType::Ones,
+            ResultType::Twos,
+            ResultType::Threes,
+            ResultType::Fours,
+            ResultType::Fives,
+            ResultType::Sixes,
+            ResultType::Pair,
+            ResultType::TwoPairs,
+            ResultType::ThreePairs,
+            ResultType::ThreeOfAKind,
+            ResultType::FourOfAKind,
+            ResultType::FiveOfAKind,
+            ResultType::SmallStraight,
+            ResultType::LargeStraight,
+            ResultType::FullStraight,
+            ResultType::FullHouse,
+            ResultType::Castle,
+            ResultType::Tower,
+            ResultType::Chance,
+            ResultType::Yatsy,
+        ]
+    }
+
+    fn score(&self, result_type: ResultType, dice: &dice::DiceRoll) -> i32 {
+        match result_type {
+            ResultType::ThreePairs => dice.three_pairs(),
+            ResultType::FiveOfAKind => dice.five_of_a_kind(),
+            ResultType::FullStraight => dice.full_straight(),
+            ResultType::Castle => dice.castle(),
+            ResultType::Tower => dice.tower(),
+            ResultType::Yatsy if dice.yatsy() > 0 => 100,
+            ResultType::Yatsy => 0,
+            _ => super::Scandinavian.score(result_type, dice),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use super::Ruleset;
+use crate::dice;
+use crate::dice_result::ResultType;
+
+// Triple Yatzy is scored like Scandinavian Yatzy, but on a card with three
+// columns worth one, two and three times their total. Every column has its
+// own upper section bonus.
+pub struct Triple;
+
+impl Ruleset for Triple {
+    fn name(&self) -> &str {
+        "Triple Yatzy"
+    }
+
+    fn columns(&self) -> usize {
+        3
+    }
+
+    fn column_multiplier(&self, column: usize) -> i32 {
+        column as i32 + 1
+    }
+
+    fn categories(&self) -> Vec<ResultType> {
+        super::Scandinavian.categories()
+    }
+
+    fn score(&self, result_type: ResultType, dice: &dice::DiceRoll) -> i32 {
+        super::Scandinavian.score(result_type, dice)
+    }
+}
@@ -0,0 +1,124 @@
+use super::Ruleset;
+use crate::dice;
+use crate::dice_result::ResultType;
+use crate::scores::ScoreCard;
+
+// American Yahtzee. The kinds score the sum of all dice, the straights and
+// the full house have fixed values, and extra Yahtzees are worth a bonus and
+// may be used as jokers.
+pub struct Yahtzee;
+
+impl Yahtzee {
+    // Joker rules apply when a Yahtzee is rolled and the Yahtzee box is already filled
+    fn is_joker(&self, dice: &dice::DiceRoll, card: &ScoreCard) -> bool {
+        dice.yatsy() > 0 && card.get_score(ResultType::Yatsy, 0).is_some()
+    }
+}
+
+impl Ruleset for Yahtzee {
+    fn name(&self) -> &str {
+        "Yahtzee"
+    }
+
+    fn bonus_amount(&self) -> i32 {
+        35
+    }
+
+    fn categories(&self) -> Vec<ResultType> {
+        vec![
+            ResultType::Ones,
+            ResultType::Twos,
+            ResultType::Threes,
+            ResultType::Fours,
+            ResultType::Fives,
+            ResultType::Sixes,
+            ResultType::ThreeOfAKind,
+            ResultType::FourOfAKind,
+            ResultType::FullHouse,
+            ResultType::SmallStraight,
+            ResultType::LargeStraight,
+            ResultType::Yatsy,
+            ResultType::Chance,
+        ]
+    }
+
+    fn score(&self, result_type: ResultType, dice: &dice::DiceRoll) -> i32 {
+        match result_type {
+            ResultType::ThreeOfAKind => dice.three_of_a_kind_total(),
+            ResultType::FourOfAKind => dice.four_of_a_kind_total(),
+            ResultType::FullHouse if dice.full_house() > 0 => 25,
+            ResultType::SmallStraight if dice.longest_straight() >= 4 => 30,
+            ResultType::LargeStraight if dice.longest_straight() >= 5 => 40,
+            ResultType::FullHouse | ResultType::SmallStraight | ResultType::LargeStraight => 0,
+            _ => super::Scandinavian.score(result_type, dice),
+        }
+    }
+
+    fn open_types(&self, dice: &dice::DiceRoll, card: &ScoreCard) -> Vec<ResultType> {
+        let open = card.get_available_types();
+        if !self.is_joker(dice, card) {
+            return open;
+        }
+
+        // The matching upper box must be used if it is open, otherwise any
+        // lower box, and only when those are all filled an upper box
+        let upper = ResultType::for_value(dice.dice[0]).unwrap();
+        if open.contains(&upper) {
+            return vec![upper];
+        }
+
+        let lower = open.iter().filter(|&&t| !self.is_upper(t)).cloned().collect::<Vec<_>>();
+        if !lower.is_empty() {
+            return lower;
+        }
+        open
+    }
+
+    fn score_on_card(&self, result_type: ResultType, dice: &dice::DiceRoll, _rerolls_used: i32, card: &ScoreCard) -> i32 {
+        if !self.is_joker(dice, card) {
+            return self.score(result_type, dice);
+        }
+
+        match result_type {
+            ResultType::FullHouse => 25,
+            ResultType::SmallStraight => 30,
+            ResultType::LargeStraight => 40,
+            _ => self.score(result_type, dice),
+        }
+    }
+
+    fn extra_bonus(&self, dice: &dice::DiceRoll, card: &ScoreCard) -> i32 {
+        if dice.yatsy() > 0 && card.get_score(ResultType::Yatsy, 0) == Some(50) {
+            return 100;
+        }
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice_result::{get_available_results, DiceResult};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_joker() {
+        let mut card = ScoreCard::new(Arc::new(Yahtzee));
+        let fours = dice::DiceRoll { dice: vec![4, 4, 4, 4, 4], faces: 6 };
+        assert_eq!(Yahtzee.extra_bonus(&fours, &card), 0);
+        card.add_result(DiceResult { result_type: ResultType::Yatsy, score: 50 }, 0);
+
+        // Upper box is open, so it has to be used
+        assert_eq!(get_available_results(&Yahtzee, &fours, 0, &card), vec![
+            DiceResult { result_type: ResultType::Fours, score: 20 },
+        ]);
+        assert_eq!(Yahtzee.extra_bonus(&fours, &card), 100);
+
+        // Upper box is filled, so the lower boxes score their full value
+        card.add_result(DiceResult { result_type: ResultType::Fours, score: 12 }, 0);
+        let results = get_available_results(&Yahtzee, &fours, 0, &card);
+        assert!(results.contains(&DiceResult { result_type: ResultType::LargeStraight, score: 40 }));
+        assert!(results.contains(&DiceResult { result_type: ResultType::FullHouse, score: 25 }));
+        assert!(results.iter().all(|r| !Yahtzee.is_upper(r.result_type)));
+    }
+}
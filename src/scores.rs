@@ -1,140 +1,358 @@
-use super::dice_result;
-use std::fmt::Display;
-use std::fmt::Formatter;
-use std::fmt;
-
-#[derive(Debug, Copy, Clone)]
-pub struct Score {
-    score_type:  dice_result::ResultType,
-    score: i32,
-    striked: bool
-}
-
-impl Score {
-    pub fn scored(&self) -> bool {
-        self.striked || self.score != 0 
-    }
-}
-
-impl Display for Score {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        if self.striked {
-            write!(f, "x\t{}", self.score_type)
-        } else {
-            write!(f, "{}p\t{}", self.score, self.score_type)
-        }
-    }
-}
-
-pub struct ScoreCard {
-    scores: [Score; 15]
-}
-
-impl ScoreCard {
-    pub fn new() -> ScoreCard {
-        ScoreCard {
-            scores: [
-                Score { score_type: dice_result::ResultType::Ones, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::Twos, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::Threes, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::Fours, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::Fives, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::Sixes, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::Pair, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::TwoPairs, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::ThreeOfAKind, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::FourOfAKind, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::SmallStraight, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::LargeStraight, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::FullHouse, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::Chance, score: 0, striked: false },
-                Score { score_type: dice_result::ResultType::Yatsy, score: 0, striked: false },
-            ]
-        }
-    }
-
-    pub fn is_complete(&self) -> bool {
-        self.scores.iter().all(|s| s.scored())
-    }
-
-    pub fn get_available_types(&self) -> Vec<dice_result::ResultType> {
-        self.scores.iter().filter(|s| !s.scored()).clone().map(|&s| s.score_type).collect()
-    }
-
-    pub fn add_result(&mut self, result: dice_result::DiceResult) {
-        let available_types = self.get_available_types();
-        if !available_types.contains(&result.result_type) {
-            return
-        }
-
-        let score_index = self.scores.iter().position(|s| s.score_type == result.result_type).unwrap();
-        self.scores[score_index] = Score { score_type: result.result_type, score: result.score, striked: false };
-    }
-
-    pub fn strike(&mut self, result_type: dice_result::ResultType) {
-        let score_index = self.scores.iter().position(|s| s.score_type == result_type).unwrap();
-        self.scores[score_index].striked = true;
-    }
-
-    pub fn print_scores(&self) {
-        println!("\n--- Score Card ---");
-
-        let upper_scores = self.get_upper_scores();
-        let lower_scores = self.get_lower_scores();
-
-        self.print_upper_scores(&upper_scores);
-        self.print_lower_scores(&lower_scores);
-
-        let total = upper_scores.into_iter().map(|f| f.score).sum::<i32>() + 
-                       lower_scores.into_iter().map(|f| f.score).sum::<i32>() + 
-                       self.get_bonus();
-        println!("Total:\t{}p", total);
-    }
- 
-    fn print_upper_scores(&self, scores: &Vec<Score>) {
-        if scores.len() == 0 {
-            return
-        }
-        
-        let mut sum = 0;
-        for score in scores {
-            println!("{}", score);
-            sum += score.score;
-        }
-        println!("---\nSum: {}p", sum);
-
-        if scores.into_iter().all(|s| s.scored() && scores.into_iter().count() == 6) {
-            println!("Bonus: {}p", self.get_bonus());
-        }
-        println!("---");
-    }
-
-    fn print_lower_scores(&self, scores: &Vec<Score>) {
-        if scores.len() == 0 {
-            return
-        }
-        
-        for score in scores {
-            println!("{}", score);
-        }
-
-        println!("---");
-    }
-
-    fn get_upper_scores(&self) -> Vec<Score> {
-        self.scores.iter().take(6).cloned().collect()
-    }
-
-    fn get_lower_scores(&self) -> Vec<Score> {
-        self.scores.iter().skip(6).cloned().collect()
-    }
-
-    fn get_bonus(&self) -> i32 {
-        let upper_sum = self.scores.iter().take(6).map(|s| s.score).sum::<i32>();
-        if upper_sum >= 63 {
-            50
-        } else {
-            0
-        }
-    }
-}
\ No newline at end of file
+use super::dice_result;
+use super::ruleset;
+use std::sync::Arc;
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Cell {
+    Empty,
+    Scored(i32),
+    Struck
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Score {
+    score_type:  dice_result::ResultType,
+    column: usize,
+    cell: Cell
+}
+
+impl Score {
+    pub fn scored(&self) -> bool {
+        self.cell != Cell::Empty
+    }
+
+    pub fn points(&self) -> i32 {
+        match self.cell {
+            Cell::Scored(score) => score,
+            Cell::Empty | Cell::Struck => 0
+        }
+    }
+}
+
+// The card has a column of scores for every column of the ruleset. Scores are
+// stored as rolled, the column multiplier is applied to the column total.
+#[derive(Clone)]
+pub struct ScoreCard {
+    ruleset: Arc<dyn ruleset::Ruleset>,
+    scores: Vec<Score>,
+    extra_bonus: i32,
+    forced: bool
+}
+
+impl ScoreCard {
+    pub fn new(ruleset: Arc<dyn ruleset::Ruleset>) -> ScoreCard {
+        let mut scores = Vec::<Score>::new();
+        for column in 0..ruleset.columns() {
+            for score_type in ruleset.categories() {
+                scores.push(Score { score_type, column, cell: Cell::Empty });
+            }
+        }
+        ScoreCard { ruleset, scores, extra_bonus: 0, forced: false }
+    }
+
+    // A card where the categories must be filled in order from top to bottom
+    pub fn new_forced(ruleset: Arc<dyn ruleset::Ruleset>) -> ScoreCard {
+        ScoreCard { forced: true, ..ScoreCard::new(ruleset) }
+    }
+
+    pub fn is_forced(&self) -> bool {
+        self.forced
+    }
+
+    pub fn reset(&mut self) {
+        for score in self.scores.iter_mut() {
+            score.cell = Cell::Empty;
+        }
+        self.extra_bonus = 0;
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.scores.iter().all(|s| s.scored())
+    }
+
+    pub fn get_available_types(&self) -> Vec<dice_result::ResultType> {
+        let open = self.get_open_scores();
+        self.ruleset.categories().into_iter()
+            .filter(|&t| open.iter().any(|s| s.score_type == t))
+            .collect()
+    }
+
+    // The columns where the category can still be filled
+    pub fn get_open_columns(&self, result_type: dice_result::ResultType) -> Vec<usize> {
+        self.get_open_scores().iter()
+            .filter(|s| s.score_type == result_type)
+            .map(|s| s.column)
+            .collect()
+    }
+
+    // The score of a category in a column, None if it hasn't been filled yet
+    pub fn get_score(&self, result_type: dice_result::ResultType, column: usize) -> Option<i32> {
+        self.find_score(result_type, column).filter(|s| s.scored()).map(|s| s.points())
+    }
+
+    pub fn get_cell(&self, result_type: dice_result::ResultType, column: usize) -> Cell {
+        self.find_score(result_type, column).map_or(Cell::Empty, |s| s.cell)
+    }
+
+    pub fn add_extra_bonus(&mut self, points: i32) {
+        self.extra_bonus += points;
+    }
+
+    pub fn add_result(&mut self, result: dice_result::DiceResult, column: usize) {
+        if !self.get_open_columns(result.result_type).contains(&column) {
+            return
+        }
+
+        let score_index = self.get_index(result.result_type, column).unwrap();
+        self.scores[score_index].cell = Cell::Scored(result.score);
+    }
+
+    pub fn strike(&mut self, result_type: dice_result::ResultType, column: usize) {
+        if !self.get_open_columns(result_type).contains(&column) {
+            return
+        }
+
+        let score_index = self.get_index(result_type, column).unwrap();
+        self.scores[score_index].cell = Cell::Struck;
+    }
+
+    // The final score, which for some rules is a point total rather than the sum of the card
+    pub fn get_total(&self) -> i32 {
+        match self.ruleset.score_points(self) {
+            Some(points) => points.iter().map(|(_, p)| p).sum(),
+            None => self.get_card_total()
+        }
+    }
+
+    // The sum of every cell on the card, with bonuses and multipliers
+    pub fn get_card_total(&self) -> i32 {
+        (0..self.ruleset.columns())
+            .map(|column| self.get_column_total(column) * self.ruleset.column_multiplier(column))
+            .sum::<i32>() + self.extra_bonus
+    }
+
+    // All bonuses on the card, with column multipliers
+    pub fn get_bonus_total(&self) -> i32 {
+        (0..self.ruleset.columns())
+            .map(|column| self.get_bonus(column) * self.ruleset.column_multiplier(column))
+            .sum::<i32>() + self.extra_bonus
+    }
+
+    // How many columns made the upper bonus, plus one for any extra bonus
+    pub fn get_bonus_count(&self) -> usize {
+        let columns = (0..self.ruleset.columns()).filter(|&c| self.get_bonus(c) > 0).count();
+        columns + usize::from(self.extra_bonus > 0)
+    }
+
+    // The sum of a category over all columns
+    pub fn get_category_total(&self, result_type: dice_result::ResultType) -> i32 {
+        self.scores.iter().filter(|s| s.score_type == result_type).map(|s| s.points()).sum()
+    }
+
+    pub fn get_cells(&self, result_type: dice_result::ResultType) -> Vec<Cell> {
+        self.scores.iter().filter(|s| s.score_type == result_type).map(|s| s.cell).collect()
+    }
+
+    pub fn format_scores(&self) -> String {
+        let mut lines = vec!["\n--- Score Card ---".to_string()];
+
+        let columns = self.ruleset.columns();
+        let multipliers = self.has_multipliers();
+        if multipliers {
+            let header = (0..columns).map(|c| format!("x{}", self.ruleset.column_multiplier(c))).collect::<Vec<_>>();
+            lines.push(header.join("\t"));
+        }
+
+        let upper_types = self.ruleset.categories().into_iter().filter(|&t| self.ruleset.is_upper(t)).collect::<Vec<_>>();
+        let lower_types = self.ruleset.categories().into_iter().filter(|&t| !self.ruleset.is_upper(t)).collect::<Vec<_>>();
+
+        self.format_upper_scores(&mut lines, &upper_types);
+        self.format_lower_scores(&mut lines, &lower_types);
+
+        if self.extra_bonus > 0 {
+            lines.push(format!("Extra bonus: {}p\n---", self.extra_bonus));
+        }
+
+        if multipliers {
+            lines.push(format_row("Column total", (0..columns).map(|c| format!("{}p", self.get_column_total(c) * self.ruleset.column_multiplier(c)))));
+        }
+
+        match self.ruleset.score_points(self) {
+            Some(points) => {
+                lines.push(format!("Score:\t{}p\n--- Points ---", self.get_card_total()));
+                for (label, p) in points {
+                    lines.push(format!("{}\t{}", p, label));
+                }
+                lines.push(format!("---\nTotal:\t{} points", self.get_total()));
+            },
+            None => lines.push(format!("Total:\t{}p", self.get_total()))
+        }
+        lines.join("\n")
+    }
+
+    fn has_multipliers(&self) -> bool {
+        (0..self.ruleset.columns()).any(|c| self.ruleset.column_multiplier(c) != self.ruleset.column_multiplier(0))
+    }
+ 
+    fn format_upper_scores(&self, lines: &mut Vec<String>, types: &[dice_result::ResultType]) {
+        if types.is_empty() {
+            return
+        }
+        
+        for &result_type in types {
+            lines.push(self.format_category(result_type));
+        }
+        lines.push("---".to_string());
+
+        let columns = 0..self.ruleset.columns();
+        lines.push(format_row("Sum", columns.clone().map(|c| format!("{}p", self.get_upper_sum(c)))));
+
+        if columns.clone().any(|c| self.is_upper_complete(c)) {
+            lines.push(format_row("Bonus", columns.map(|c| {
+                if self.is_upper_complete(c) {
+                    format!("{}p", self.get_bonus(c))
+                } else {
+                    "-".to_string()
+                }
+            })));
+        }
+        lines.push("---".to_string());
+    }
+
+    fn format_lower_scores(&self, lines: &mut Vec<String>, types: &[dice_result::ResultType]) {
+        if types.is_empty() {
+            return
+        }
+        
+        for &result_type in types {
+            lines.push(self.format_category(result_type));
+        }
+
+        lines.push("---".to_string());
+    }
+
+    fn format_category(&self, result_type: dice_result::ResultType) -> String {
+        let cells = (0..self.ruleset.columns()).map(|column| self.format_cell(result_type, column));
+        format_row(&self.ruleset.category_name(result_type), cells)
+    }
+
+    pub fn format_cell(&self, result_type: dice_result::ResultType, column: usize) -> String {
+        match self.get_cell(result_type, column) {
+            Cell::Empty => "-".to_string(),
+            Cell::Scored(score) => format!("{}p", score),
+            Cell::Struck => "x".to_string(),
+        }
+    }
+
+    fn get_open_scores(&self) -> Vec<Score> {
+        let open = self.scores.iter().filter(|s| !s.scored());
+        if self.forced {
+            // Only the topmost open category of each column can be filled
+            return (0..self.ruleset.columns())
+                .filter_map(|column| open.clone().find(|s| s.column == column))
+                .cloned()
+                .collect();
+        }
+        open.cloned().collect()
+    }
+
+    fn find_score(&self, result_type: dice_result::ResultType, column: usize) -> Option<&Score> {
+        self.scores.iter().find(|s| s.score_type == result_type && s.column == column)
+    }
+
+    fn get_index(&self, result_type: dice_result::ResultType, column: usize) -> Option<usize> {
+        self.scores.iter().position(|s| s.score_type == result_type && s.column == column)
+    }
+
+    fn get_upper_scores(&self, column: usize) -> Vec<Score> {
+        self.scores.iter().filter(|s| s.column == column && self.ruleset.is_upper(s.score_type)).cloned().collect()
+    }
+
+    fn is_upper_complete(&self, column: usize) -> bool {
+        self.get_upper_scores(column).iter().all(|s| s.scored())
+    }
+
+    fn get_upper_sum(&self, column: usize) -> i32 {
+        self.get_upper_scores(column).iter().map(|s| s.points()).sum::<i32>()
+    }
+
+    fn get_column_total(&self, column: usize) -> i32 {
+        self.scores.iter().filter(|s| s.column == column).map(|s| s.points()).sum::<i32>() + self.get_bonus(column)
+    }
+
+    fn get_bonus(&self, column: usize) -> i32 {
+        self.get_bonus_for_sum(self.get_upper_sum(column))
+    }
+
+    // The bonus an upper section adding up to the given sum would get
+    pub fn get_bonus_for_sum(&self, upper_sum: i32) -> i32 {
+        if upper_sum >= self.ruleset.bonus_threshold() {
+            self.ruleset.bonus_amount()
+        } else {
+            0
+        }
+    }
+}
+
+// The cells of all columns side by side, followed by the label
+fn format_row(label: &str, cells: impl Iterator<Item = String>) -> String {
+    format!("{}\t{}", cells.collect::<Vec<_>>().join("\t"), label)
+}
+
+// A table with a column per player, as given by Game::scoreboard
+pub fn format_board(names: &[String], rows: &[(String, Vec<String>)]) -> String {
+    let mut lines = vec!["\n--- Scoreboard ---".to_string(), names.join("\t")];
+    for (label, cells) in rows {
+        if label == "Bonus" {
+            lines.push("---".to_string());
+        }
+        lines.push(format!("{}\t{}", cells.join("\t"), label));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forced_order() {
+        let mut card = ScoreCard::new_forced(Arc::new(ruleset::Scandinavian));
+        assert_eq!(card.get_available_types(), vec![dice_result::ResultType::Ones]);
+
+        card.strike(dice_result::ResultType::Ones, 0);
+        assert_eq!(card.get_available_types(), vec![dice_result::ResultType::Twos]);
+
+        // Results for other categories are ignored
+        card.add_result(dice_result::DiceResult { result_type: dice_result::ResultType::Pair, score: 12 }, 0);
+        assert_eq!(card.get_score(dice_result::ResultType::Pair, 0), None);
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut card = ScoreCard::new(Arc::new(ruleset::Triple));
+        let sixes = dice_result::DiceResult { result_type: dice_result::ResultType::Sixes, score: 18 };
+        card.add_result(sixes, 2);
+        assert_eq!(card.get_open_columns(dice_result::ResultType::Sixes), vec![0, 1]);
+        assert_eq!(card.get_total(), 54);
+
+        card.add_result(sixes, 1);
+        card.add_result(sixes, 0);
+        assert!(!card.get_available_types().contains(&dice_result::ResultType::Sixes));
+        assert_eq!(card.get_total(), 108);
+    }
+
+    #[test]
+    fn test_zero_is_not_empty() {
+        let mut card = ScoreCard::new(Arc::new(ruleset::Scandinavian));
+        card.add_result(dice_result::DiceResult { result_type: dice_result::ResultType::Chance, score: 0 }, 0);
+        card.strike(dice_result::ResultType::Yatsy, 0);
+
+        assert_eq!(card.get_cell(dice_result::ResultType::Chance, 0), Cell::Scored(0));
+        assert_eq!(card.get_cell(dice_result::ResultType::Yatsy, 0), Cell::Struck);
+        assert_eq!(card.get_cell(dice_result::ResultType::Pair, 0), Cell::Empty);
+        assert_eq!(card.get_score(dice_result::ResultType::Chance, 0), Some(0));
+        assert!(!card.get_available_types().contains(&dice_result::ResultType::Chance));
+        assert_eq!(card.get_total(), 0);
+    }
+}
@@ -0,0 +1,136 @@
+use std::io;
+use std::sync::Arc;
+
+use super::dice_result::ResultType;
+use super::game::Game;
+use super::input::Console;
+use super::ruleset::Ruleset;
+use super::strategy::{self, Strategy};
+
+const HISTOGRAM_WIDTH: i32 = 10;
+const BAR_LENGTH: usize = 40;
+
+// The results of many solo games played by one strategy
+pub struct Summary {
+    pub strategy: String,
+    // Final score of every game, lowest first
+    pub totals: Vec<i32>,
+    // Games with any bonus, and with a Yatsy scored
+    pub bonuses: usize,
+    pub yatsies: usize,
+    pub categories: Vec<(String, f64)>,
+}
+
+impl Summary {
+    pub fn mean(&self) -> f64 {
+        self.totals.iter().sum::<i32>() as f64 / self.totals.len() as f64
+    }
+
+    pub fn median(&self) -> f64 {
+        let middle = self.totals.len() / 2;
+        if self.totals.len().is_multiple_of(2) {
+            (self.totals[middle - 1] + self.totals[middle]) as f64 / 2.0
+        } else {
+            self.totals[middle] as f64
+        }
+    }
+
+    pub fn standard_deviation(&self) -> f64 {
+        let mean = self.mean();
+        let variance = self.totals.iter().map(|&t| (t as f64 - mean).powi(2)).sum::<f64>() / self.totals.len() as f64;
+        variance.sqrt()
+    }
+
+    // Games per range of scores, as (lowest score of the range, games)
+    pub fn histogram(&self) -> Vec<(i32, usize)> {
+        let bin = |total: i32| total.div_euclid(HISTOGRAM_WIDTH) * HISTOGRAM_WIDTH;
+        let (first, last) = (bin(self.totals[0]), bin(*self.totals.last().unwrap()));
+        (first..=last).step_by(HISTOGRAM_WIDTH as usize)
+            .map(|low| (low, self.totals.iter().filter(|&&t| bin(t) == low).count()))
+            .collect()
+    }
+
+    pub fn format(&self) -> String {
+        let games = self.totals.len();
+        let percent = |n: usize| 100.0 * n as f64 / games as f64;
+        let mut lines = vec![
+            format!("--- {} games by {} ---", games, self.strategy),
+            format!("Mean {:.1}p, median {:.1}p, standard deviation {:.1}p", self.mean(), self.median(), self.standard_deviation()),
+            format!("Lowest {}p, highest {}p", self.totals[0], self.totals.last().unwrap()),
+            format!("Bonus in {:.1}% of games, Yatsy in {:.1}%", percent(self.bonuses), percent(self.yatsies)),
+            "\n--- Scores ---".to_string(),
+        ];
+
+        let most = self.histogram().iter().map(|&(_, n)| n).max().unwrap_or(1);
+        for (low, n) in self.histogram() {
+            let bar = "#".repeat((n * BAR_LENGTH).div_ceil(most));
+            lines.push(format!("{}-{}p\t{:.1}%\t{}", low, low + HISTOGRAM_WIDTH - 1, percent(n), bar));
+        }
+
+        lines.push("\n--- Average per category ---".to_string());
+        for (name, average) in &self.categories {
+            lines.push(format!("{:.2}p\t{}", average, name));
+        }
+        lines.join("\n")
+    }
+}
+
+// Plays the games without any input or output. Game i is seeded with seed + i,
+// so the same seed plays the same games.
+pub fn simulate(ruleset: Arc<dyn Ruleset>, forced: bool, strategy: &mut dyn Strategy, games: usize, seed: u64) -> Result<Summary, String> {
+    if games == 0 {
+        return Err("Simulate at least one game".to_string());
+    }
+
+    let categories = ruleset.categories();
+    let mut summary = Summary { strategy: strategy.name(), totals: Vec::new(), bonuses: 0, yatsies: 0, categories: Vec::new() };
+    let mut category_totals = vec![0; categories.len()];
+
+    for i in 0..games {
+        let console = Console::new(io::empty(), io::sink());
+        let mut game = Game::with_console(ruleset.clone(), forced, vec![strategy.name()], console)
+            .with_seed(seed.wrapping_add(i as u64), false);
+        while !game.is_over() {
+            strategy::play_turn(&mut game, strategy)?;
+        }
+
+        let card = &game.players[0].score_card;
+        summary.totals.push(card.get_total());
+        summary.bonuses += usize::from(card.get_bonus_count() > 0);
+        summary.yatsies += usize::from(card.get_category_total(ResultType::Yatsy) > 0);
+        for (total, &t) in category_totals.iter_mut().zip(&categories) {
+            *total += card.get_category_total(t);
+        }
+    }
+
+    summary.totals.sort();
+    summary.categories = categories.iter().zip(category_totals)
+        .map(|(&t, total)| (ruleset.category_name(t), total as f64 / games as f64))
+        .collect();
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{Bot, Difficulty};
+    use crate::ruleset::Scandinavian;
+
+    #[test]
+    fn test_simulate() {
+        let play = || {
+            let mut bot = Bot::new(Difficulty::Heuristic, Arc::new(Scandinavian), false).unwrap();
+            simulate(Arc::new(Scandinavian), false, &mut bot, 20, 5).unwrap()
+        };
+        let (summary, again) = (play(), play());
+        assert_eq!(summary.totals, again.totals);
+
+        assert_eq!(summary.totals.len(), 20);
+        assert_eq!(summary.histogram().iter().map(|&(_, n)| n).sum::<usize>(), 20);
+        assert!(summary.totals[0] as f64 <= summary.median() && summary.median() <= summary.totals[19] as f64);
+        // Without bonuses the categories add up to the mean
+        if summary.bonuses == 0 {
+            assert!((summary.categories.iter().map(|(_, a)| a).sum::<f64>() - summary.mean()).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::dice;
+use super::dice_result::{self, ResultType};
+use super::outcomes::Outcomes;
+use super::ruleset::Ruleset;
+use super::scores;
+
+const MAGIC: &[u8; 4] = b"YSLV";
+
+// The most states the solver keeps values for, 64 MB of them
+const MAX_STATES: usize = 1 << 24;
+
+// Numbers the temporary files of saves, which can run at the same time
+static SAVES: AtomicUsize = AtomicUsize::new(0);
+
+// Computes the strategy that maximizes the expected score of a solo game, by
+// backward induction over the states between turns (the filled categories and
+// the upper sum so far) and over the keep or reroll choices within a turn.
+//
+// The value of a state is the expected score of the rest of the game. Values
+// are computed when first needed and can be cached to disk, since solving a
+// game from an empty card takes a while. Scoring that depends on the card,
+// like Yahtzee jokers, and served hands aren't taken into account. Forced play
+// isn't either, so the solver refuses it.
+pub struct Solver {
+    categories: Vec<ResultType>,
+    upper: Vec<bool>,
+    // Upper sums are capped here, above it they're all worth the same
+    threshold: usize,
+    bonus: i32,
+    rerolls: usize,
+    outcomes: Outcomes,
+    // The score of each outcome in each category
+    scores: Vec<Vec<i32>>,
+    // Indexed by filled categories and upper sum, NaN until computed
+    values: Vec<f32>,
+    fingerprint: u64,
+}
+
+impl Solver {
+    pub fn new(ruleset: Arc<dyn Ruleset>, forced: bool) -> Result<Solver, String> {
+        if forced {
+            return Err("The solver can't handle forced play, where the categories are filled in order".to_string());
+        }
+        let categories = ruleset.categories();
+        if ruleset.columns() != 1 || categories.len() > 16 || ruleset.dice_count() > 6 {
+            return Err(format!("The solver can't handle {}, it needs a single column of at most 16 categories and 6 dice", ruleset.name()));
+        }
+        let card = scores::ScoreCard::new(ruleset.clone());
+        if ruleset.score_points(&card).is_some() {
+            return Err(format!("The solver can't handle the point scoring of {}", ruleset.name()));
+        }
+
+        let dice_count = ruleset.dice_count();
+        let faces = ruleset.faces();
+        let bonus = card.get_bonus_for_sum(ruleset.bonus_threshold());
+        let threshold = if bonus > 0 { ruleset.bonus_threshold().max(0) as usize } else { 0 };
+        let states = (1usize << categories.len()).saturating_mul(threshold + 1);
+        if states > MAX_STATES {
+            return Err(format!("The solver can't handle {}, the bonus threshold is too high to go through every card", ruleset.name()));
+        }
+
+        let outcomes = Outcomes::new(dice_count, faces);
+        let scores = outcomes.outcomes().iter().map(|outcome| {
+            let roll = dice::DiceRoll { dice: outcome.clone(), faces };
+            let results = dice_result::get_results(ruleset.as_ref(), &roll, 1);
+            categories.iter()
+                .map(|&t| results.iter().find(|r| r.result_type == t).map_or(0, |r| r.score))
+                .collect()
+        }).collect::<Vec<Vec<i32>>>();
+
+        let upper = categories.iter().map(|&t| ruleset.is_upper(t)).collect::<Vec<_>>();
+        let rerolls = ruleset.rerolls_per_turn().max(0) as usize;
+
+        let mut hasher = DefaultHasher::new();
+        (dice_count, faces, rerolls, threshold, bonus, &upper, &scores).hash(&mut hasher);
+
+        Ok(Solver {
+            values: vec![f32::NAN; states],
+            categories,
+            upper,
+            threshold,
+            bonus,
+            rerolls,
+            outcomes,
+            scores,
+            fingerprint: hasher.finish(),
+        })
+    }
+
+    // A solver with the values from the cache file, if there is one for the same rules
+    pub fn cached(ruleset: Arc<dyn Ruleset>, forced: bool) -> Result<Solver, String> {
+        let mut solver = Solver::new(ruleset, forced)?;
+        // A missing or damaged file only means starting over, the values are left alone
+        let _ = solver.load(&solver.cache_path());
+        Ok(solver)
+    }
+
+    // The cache file is named after the rules, so changed rules get a new one
+    pub fn cache_path(&self) -> PathBuf {
+        std::env::temp_dir().join(format!("yatsy-solver-{:016x}.bin", self.fingerprint))
+    }
+
+    pub fn load(&mut self, path: &Path) -> Result<(), String> {
+        let bytes = fs::read(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        let header = MAGIC.len() + 8;
+        if bytes.len() != header + self.values.len() * 4 || &bytes[..4] != MAGIC || bytes[4..header] != self.fingerprint.to_le_bytes() {
+            return Err(format!("{} doesn't hold values for these rules", path.display()));
+        }
+
+        for (value, chunk) in self.values.iter_mut().zip(bytes[header..].chunks_exact(4)) {
+            *value = f32::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(self.fingerprint.to_le_bytes());
+        for value in &self.values {
+            bytes.extend(value.to_le_bytes());
+        }
+
+        // Written next to the file and moved over it, so a reader never sees half a file
+        let temp = path.with_extension(format!("{}-{}.tmp", process::id(), SAVES.fetch_add(1, Ordering::Relaxed)));
+        let result = fs::write(&temp, bytes).and_then(|_| fs::rename(&temp, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        result.map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+
+    // Computes every state reachable from an empty card, returns the expected score of a game
+    pub fn solve(&mut self) -> f64 {
+        self.state_value(0, 0)
+    }
+
+    // The expected final score of a card between turns
+    pub fn expected_score(&mut self, card: &scores::ScoreCard) -> f64 {
+        let (filled, upper) = self.state(card);
+        card.get_total() as f64 + self.state_value(filled, upper)
+    }
+
+    // The dice to hold with the rerolls left, as indices into the dice, with
+    // the expected final score of each choice. Best first.
+    pub fn keep_values(&mut self, dice: &dice::DiceRoll, rerolls_left: i32, card: &scores::ScoreCard) -> Vec<(Vec<usize>, f64)> {
+        let (filled, upper) = self.state(card);
+        let open = self.open_categories(card);
+        let final_values = self.final_values(filled, upper, &open);
+        let total = card.get_total() as f64;
+        if rerolls_left <= 0 {
+            return vec![((0..dice.dice.len()).collect(), total + final_values[self.outcome(dice)])];
+        }
+
+        let values = self.outcomes.roll_values(final_values, rerolls_left as usize - 1);
+        let keep_values = self.outcomes.expected_values(&values);
+        let mut choices = Vec::<(usize, Vec<usize>, f64)>::new();
+        for subset in 0..1 << dice.dice.len() {
+            let held = (0..dice.dice.len()).filter(|i| subset & (1 << i) != 0).collect::<Vec<_>>();
+            let keep = self.outcomes.keep(&held.iter().map(|&i| dice.dice[i]).collect::<Vec<_>>()).unwrap();
+            if choices.iter().all(|&(k, _, _)| k != keep) {
+                choices.push((keep, held, total + keep_values[keep]));
+            }
+        }
+        choices.sort_by(|a, b| b.2.total_cmp(&a.2));
+        choices.into_iter().map(|(_, held, value)| (held, value)).collect()
+    }
+
+    pub fn best_keep(&mut self, dice: &dice::DiceRoll, rerolls_left: i32, card: &scores::ScoreCard) -> (Vec<usize>, f64) {
+        self.keep_values(dice, rerolls_left, card).swap_remove(0)
+    }
+
+    // The open categories the dice can go in, with the expected final score
+    // of placing them there. Best first.
+    pub fn category_values(&mut self, dice: &dice::DiceRoll, card: &scores::ScoreCard) -> Vec<(ResultType, f64)> {
+        let (filled, upper) = self.state(card);
+        let outcome = self.outcome(dice);
+        let mut choices = self.open_categories(card).into_iter()
+            .map(|c| (self.categories[c], card.get_total() as f64 + self.choice_value(outcome, c, filled, upper)))
+            .collect::<Vec<_>>();
+        choices.sort_by(|a, b| b.1.total_cmp(&a.1));
+        choices
+    }
+
+    pub fn best_category(&mut self, dice: &dice::DiceRoll, card: &scores::ScoreCard) -> Option<(ResultType, f64)> {
+        self.category_values(dice, card).into_iter().next()
+    }
+
+    // Filled categories as bits, and the capped upper sum
+    fn state(&self, card: &scores::ScoreCard) -> (usize, usize) {
+        let mut filled = 0;
+        let mut upper = 0;
+        for (c, &t) in self.categories.iter().enumerate() {
+            if let Some(score) = card.get_score(t, 0) {
+                filled |= 1 << c;
+                if self.upper[c] {
+                    upper += score;
+                }
+            }
+        }
+        (filled, (upper.max(0) as usize).min(self.threshold))
+    }
+
+    fn open_categories(&self, card: &scores::ScoreCard) -> Vec<usize> {
+        let available = card.get_available_types();
+        (0..self.categories.len()).filter(|&c| available.contains(&self.categories[c])).collect()
+    }
+
+    fn outcome(&self, dice: &dice::DiceRoll) -> usize {
+        self.outcomes.outcome(&dice.dice).unwrap()
+    }
+
+    fn state_value(&mut self, filled: usize, upper: usize) -> f64 {
+        if filled == (1 << self.categories.len()) - 1 {
+            return 0.0;
+        }
+        let index = filled * (self.threshold + 1) + upper;
+        if !self.values[index].is_nan() {
+            return self.values[index] as f64;
+        }
+
+        let open = (0..self.categories.len()).filter(|c| filled & (1 << c) == 0).collect::<Vec<_>>();
+        let final_values = self.final_values(filled, upper, &open);
+        let values = self.outcomes.roll_values(final_values, self.rerolls);
+        let empty = self.outcomes.keep(&[]).unwrap();
+        let value = self.outcomes.transitions(empty).iter().map(|&(o, p)| p * values[o]).sum::<f64>();
+
+        self.values[index] = value as f32;
+        value
+    }
+
+    // Score now plus the value of the state it leads to
+    fn choice_value(&mut self, outcome: usize, category: usize, filled: usize, upper: usize) -> f64 {
+        let score = self.scores[outcome][category];
+        let mut value = score as f64;
+        let mut next_upper = upper;
+        if self.upper[category] {
+            next_upper = (upper + score.max(0) as usize).min(self.threshold);
+            if upper < self.threshold && next_upper == self.threshold {
+                value += self.bonus as f64;
+            }
+        }
+        value + self.state_value(filled | (1 << category), next_upper)
+    }
+
+    // The value of each outcome after the last roll, going for the best open category
+    fn final_values(&mut self, filled: usize, upper: usize, open: &[usize]) -> Vec<f64> {
+        (0..self.outcomes.outcomes().len())
+            .map(|o| open.iter().map(|&c| self.choice_value(o, c, filled, upper)).fold(f64::MIN, f64::max))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ruleset::Scandinavian;
+
+    // A card with only the given categories left open
+    fn card_with_open(open: &[ResultType]) -> scores::ScoreCard {
+        let mut card = scores::ScoreCard::new(Arc::new(Scandinavian));
+        for result_type in Scandinavian.categories() {
+            if !open.contains(&result_type) {
+                card.strike(result_type, 0);
+            }
+        }
+        card
+    }
+
+    fn roll(dice: &[i32]) -> dice::DiceRoll {
+        dice::DiceRoll { dice: dice.to_vec(), faces: 6 }
+    }
+
+    #[test]
+    fn test_outcomes() {
+        let solver = Solver::new(Arc::new(Scandinavian), false).unwrap();
+        assert_eq!(solver.outcomes.outcomes().len(), 252);
+        assert_eq!(solver.scores.len(), 252);
+    }
+
+    #[test]
+    fn test_forced() {
+        assert!(Solver::new(Arc::new(Scandinavian), true).is_err());
+        assert!(Solver::cached(Arc::new(Scandinavian), true).is_err());
+    }
+
+    #[test]
+    fn test_too_many_states() {
+        let mut rules = "name = \"Far bonus\"\nbonus_threshold = 100000\nbonus = 50\n".to_string();
+        for value in 1..=6 {
+            rules += &format!("[[categories]]\nname = \"{}s\"\npattern = \"face\"\nvalue = {}\n", value, value);
+        }
+        for i in 0..10 {
+            rules += &format!("[[categories]]\nname = \"Chance {}\"\npattern = \"chance\"\n", i);
+        }
+        let ruleset = Arc::new(crate::ruleset::Custom::from_toml(&rules).unwrap());
+        assert!(Solver::new(ruleset, false).err().unwrap().contains("bonus threshold"));
+    }
+
+    #[test]
+    fn test_damaged_cache() {
+        // Rules of its own, so no other test shares the cache file
+        let rules = r#"
+            name = "Cache"
+            dice = 3
+            faces = 7
+            rerolls = 4
+
+            [[categories]]
+            name = "Sum"
+            pattern = "chance"
+        "#;
+        let ruleset = Arc::new(crate::ruleset::Custom::from_toml(rules).unwrap());
+        let mut solver = Solver::new(ruleset.clone(), false).unwrap();
+        let path = solver.cache_path();
+        fs::write(&path, b"YSLV").unwrap();
+
+        let mut cached = Solver::cached(ruleset, false).unwrap();
+        assert!((cached.solve() - solver.solve()).abs() < 1e-9);
+        cached.save(&path).unwrap();
+        assert!(solver.load(&path).is_ok());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_best_keep() {
+        let mut solver = Solver::new(Arc::new(Scandinavian), false).unwrap();
+        let card = card_with_open(&[ResultType::Chance]);
+
+        // Rerolling anything below a 4 beats the average of 3.5
+        let (held, value) = solver.best_keep(&roll(&[1, 6, 4, 3, 6]), 1, &card);
+        assert_eq!(held, vec![1, 2, 4]);
+        assert!((value - 23.0).abs() < 1e-9);
+        assert!((solver.expected_score(&card) - 23.33).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_best_category() {
+        let mut solver = Solver::new(Arc::new(Scandinavian), false).unwrap();
+        let card = card_with_open(&[ResultType::Sixes, ResultType::Yatsy]);
+
+        let values = solver.category_values(&roll(&[6, 6, 6, 6, 6]), &card);
+        assert_eq!(values[0].0, ResultType::Yatsy);
+        assert_eq!(values.len(), 2);
+        assert_eq!(solver.best_category(&roll(&[6, 6, 6, 6, 2]), &card).unwrap().0, ResultType::Sixes);
+    }
+}
@@ -0,0 +1,64 @@
+use super::dice::DiceRoll;
+use super::dice_result::{DiceResult, ResultType};
+use super::game::{Game, Pick};
+use super::ruleset::Ruleset;
+use super::scores::ScoreCard;
+
+// What a strategy sees when it's the current player's turn
+pub struct State<'a> {
+    pub dice: &'a DiceRoll,
+    pub rerolls: i32,
+    pub card: &'a ScoreCard,
+    pub ruleset: &'a dyn Ruleset,
+    // The results the dice can be placed as
+    pub results: Vec<DiceResult>,
+    // The rows that can be scored as zero or struck
+    pub open: Vec<ResultType>,
+}
+
+impl State<'_> {
+    pub fn new(game: &Game) -> State<'_> {
+        State {
+            dice: &game.dice,
+            rerolls: game.rerolls,
+            card: game.score_card(),
+            ruleset: game.ruleset.as_ref(),
+            results: game.available_results(),
+            open: game.open_types(),
+        }
+    }
+}
+
+// A way of playing a turn. Seat one in a game with Game::add_bot, simulate
+// games with it or pit several against each other in the arena.
+pub trait Strategy {
+    /// The name of players using the strategy
+    fn name(&self) -> String;
+
+    /// The dice to hold before the next reroll, as indices into the dice. Holding all of them picks now.
+    fn choose_keep(&mut self, state: &State) -> Vec<usize>;
+
+    /// The row to fill in with the dice, and the column for it. A `Pick::Result` must be one of
+    /// `state.results`, a `Pick::Zero` or `Pick::Strike` one of `state.open`, and the column must be
+    /// open for the row. In a game any other pick strikes the first open row instead, and `play_turn`
+    /// returns an error for it.
+    fn choose_category(&mut self, state: &State) -> (Pick, usize);
+}
+
+// Plays the current player's turn without any output
+pub fn play_turn(game: &mut Game, strategy: &mut dyn Strategy) -> Result<(), String> {
+    while game.rerolls > 0 && !game.is_over() {
+        let held = strategy.choose_keep(&State::new(game));
+        let reroll = (0..game.dice.dice.len()).filter(|i| !held.contains(i)).collect::<Vec<_>>();
+        if reroll.is_empty() {
+            break;
+        }
+        game.reroll(reroll)?;
+    }
+    if game.is_over() {
+        return Ok(());
+    }
+
+    let (pick, column) = strategy.choose_category(&State::new(game));
+    game.place(pick, column)
+}
@@ -0,0 +1,247 @@
+use super::game;
+use super::input;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Format {
+    RoundRobin,
+    Knockout,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "round-robin" | "league" => Some(Format::RoundRobin),
+            "knockout" | "cup" => Some(Format::Knockout),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Standing {
+    pub name: String,
+    pub matches: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub games_won: u32,
+    pub points: i32,
+    pub bonuses: u32,
+}
+
+impl Standing {
+    // Two for a match win and one for a draw
+    pub fn match_points(&self) -> u32 {
+        self.wins * 2 + self.draws
+    }
+}
+
+// A series of head to head matches, each the best of a number of games.
+// Games are played by a callback, so they can be played at the terminal or by bots.
+pub struct Tournament {
+    format: Format,
+    best_of: u32,
+    standings: Vec<Standing>,
+    pub console: input::Console,
+}
+
+impl Tournament {
+    pub fn new(format: Format, best_of: u32, players: Vec<String>) -> Result<Tournament, String> {
+        if players.len() < 2 {
+            return Err("A tournament needs at least 2 players".to_string());
+        }
+        if best_of == 0 {
+            return Err("Matches need at least one game".to_string());
+        }
+
+        Ok(Tournament {
+            format,
+            best_of,
+            standings: players.into_iter().map(|name| Standing { name, ..Default::default() }).collect(),
+            console: input::Console::stdio(),
+        })
+    }
+
+    // Plays every match and returns the winner. The callback gets the players
+    // of a game in turn order and returns the game once it's over.
+    pub fn run(&mut self, mut play: impl FnMut(Vec<String>) -> game::Game) -> Result<String, String> {
+        let players = (0..self.standings.len()).collect::<Vec<_>>();
+        let winner = match self.format {
+            Format::RoundRobin => {
+                for (i, &first) in players.iter().enumerate() {
+                    for &second in &players[i + 1..] {
+                        self.play_match(first, second, &mut play)?;
+                    }
+                }
+                self.standings()[0].name.clone()
+            },
+            Format::Knockout => {
+                let mut remaining = players;
+                let mut round = 1;
+                while remaining.len() > 1 {
+                    self.console.print(format!("\n=== Round {} ===", round));
+                    let mut next = Vec::new();
+                    for pair in remaining.chunks(2) {
+                        match *pair {
+                            [first, second] => {
+                                let winner = self.play_match(first, second, &mut play)?;
+                                next.push(winner.expect("knockout matches are played until someone wins"));
+                            },
+                            // Odd one out gets a bye
+                            [first] => next.push(first),
+                            _ => unreachable!(),
+                        }
+                    }
+                    remaining = next;
+                    round += 1;
+                }
+                self.standings[remaining[0]].name.clone()
+            },
+        };
+
+        self.console.print("\n=== Final standings ===");
+        self.console.print(self.format_standings());
+        self.console.print(format!("{} wins the tournament!", winner));
+        Ok(winner)
+    }
+
+    // Sorted by match points, then games won, total points and bonuses
+    pub fn standings(&self) -> Vec<&Standing> {
+        let mut standings = self.standings.iter().collect::<Vec<_>>();
+        standings.sort_by_key(|s| std::cmp::Reverse((s.match_points(), s.games_won, s.points, s.bonuses)));
+        standings
+    }
+
+    pub fn format_standings(&self) -> String {
+        let mut lines = vec!["#\tPlayed\tWon\tDrawn\tLost\tGames\tPoints\tBonuses\tPlayer".to_string()];
+        for (i, s) in self.standings().iter().enumerate() {
+            lines.push(format!("{}\t{}\t{}\t{}\t{}\t{}\t{}p\t{}\t{}",
+                i + 1, s.matches, s.wins, s.draws, s.losses, s.games_won, s.points, s.bonuses, s.name));
+        }
+        lines.join("\n")
+    }
+
+    // Returns the winner, or None for a drawn match. Knockout matches go on
+    // until someone wins, the others end after best_of games.
+    fn play_match(&mut self, first: usize, second: usize, play: &mut impl FnMut(Vec<String>) -> game::Game) -> Result<Option<usize>, String> {
+        let players = [first, second];
+        self.console.print(format!("\n--- {} vs {} ---", self.standings[first].name, self.standings[second].name));
+
+        let needed = self.best_of / 2 + 1;
+        let mut wins = [0, 0];
+        let mut played = 0;
+        while wins.iter().all(|&w| w < needed) && (self.format == Format::Knockout || played < self.best_of) {
+            // Take turns starting
+            let order = if played % 2 == 0 { [0, 1] } else { [1, 0] };
+            let game = play(order.iter().map(|&i| self.standings[players[i]].name.clone()).collect());
+            if !game.is_over() {
+                return Err("The tournament was abandoned".to_string());
+            }
+
+            let game_winner = self.record_game(&game);
+            if let Some(winner) = game_winner.and_then(|name| players.iter().position(|&p| self.standings[p].name == name)) {
+                wins[winner] += 1;
+                self.standings[players[winner]].games_won += 1;
+            }
+            played += 1;
+
+            self.console.print(format!("\nGame {}: {} {} - {} {}",
+                played, self.standings[first].name, wins[0], wins[1], self.standings[second].name));
+            self.console.print(self.format_standings());
+        }
+
+        let winner = match wins[0].cmp(&wins[1]) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        };
+        for (i, &player) in players.iter().enumerate() {
+            let standing = &mut self.standings[player];
+            standing.matches += 1;
+            match winner {
+                Some(w) if w == i => standing.wins += 1,
+                Some(_) => standing.losses += 1,
+                None => standing.draws += 1,
+            }
+        }
+        Ok(winner.map(|w| players[w]))
+    }
+
+    // Adds the totals of a finished game, returns the name of the winner unless it was a tie
+    fn record_game(&mut self, game: &game::Game) -> Option<String> {
+        for player in &game.players {
+            if let Some(standing) = self.standings.iter_mut().find(|s| s.name == player.name) {
+                standing.points += player.score_card.get_total();
+                standing.bonuses += player.score_card.get_bonus_count() as u32;
+            }
+        }
+
+        match game.ranking().as_slice() {
+            [(1, winner), (2, _), ..] => Some(winner.name.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{Bot, Difficulty};
+    use crate::ruleset::{self, Ruleset};
+    use std::io;
+    use std::sync::Arc;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let mut tournament = Tournament::new(Format::RoundRobin, 3, names(&["Kim", "Anna", "Bob"])).unwrap();
+        tournament.console = input::Console::new(io::empty(), io::sink());
+        let winner = tournament.run(|players| game::finished_game(players, |name| if name == "Anna" { 25 } else { 20 })).unwrap();
+        assert_eq!(winner, "Anna");
+
+        let standings = tournament.standings();
+        assert_eq!(standings[0], &Standing {
+            name: "Anna".to_string(), matches: 2, wins: 2, draws: 0, losses: 0, games_won: 4, points: 100, bonuses: 0
+        });
+        // Kim and Bob tie every game, and share the match
+        assert_eq!((standings[1].draws, standings[1].games_won, standings[1].points), (1, 0, 100));
+        assert_eq!(standings[2].losses, 1);
+    }
+
+    #[test]
+    fn test_knockout() {
+        let mut tournament = Tournament::new(Format::Knockout, 1, names(&["Kim", "Anna", "Bob"])).unwrap();
+        tournament.console = input::Console::new(io::empty(), io::sink());
+        let winner = tournament.run(|players| game::finished_game(players, |name| name.len() as i32)).unwrap();
+        assert_eq!(winner, "Anna");
+        assert_eq!(tournament.standings()[0].wins, 2);
+        // Bob had a bye in the first round
+        assert_eq!(tournament.standings().iter().find(|s| s.name == "Bob").unwrap().matches, 1);
+    }
+
+    #[test]
+    fn test_bots() {
+        let ruleset: Arc<dyn Ruleset> = Arc::new(ruleset::Scandinavian);
+        let mut tournament = Tournament::new(Format::Knockout, 1, names(&["Heuristic Bot", "Random Bot"])).unwrap();
+        tournament.console = input::Console::new(io::empty(), io::sink());
+
+        let winner = tournament.run(|players| {
+            let console = input::Console::new(io::empty(), io::sink());
+            let mut game = game::Game::with_console(ruleset.clone(), false, players, console);
+            for player in game.players.iter_mut() {
+                let difficulty = if player.name == "Heuristic Bot" { Difficulty::Heuristic } else { Difficulty::Random };
+                player.bot = Some(Box::new(Bot::new(difficulty, ruleset.clone(), false).unwrap()));
+            }
+            game.start();
+            game
+        }).unwrap();
+
+        assert!(["Heuristic Bot", "Random Bot"].contains(&winner.as_str()));
+        let standings = tournament.standings();
+        assert_eq!((standings[0].name.as_str(), standings[0].wins), (winner.as_str(), 1));
+        assert!(standings.iter().all(|s| s.points > 0));
+    }
+}